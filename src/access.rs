@@ -0,0 +1,89 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+
+use {CLike, EnumSet};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+/// A pair of allowed and denied sets, applied on top of a base set.
+///
+/// Denied elements win over allowed ones, see `EnumSet::resolve`.
+pub struct AccessMask<E> {
+    /// The elements added to the base set.
+    pub allow: EnumSet<E>,
+    /// The elements removed from the base set.
+    pub deny: EnumSet<E>,
+}
+
+impl<E: CLike> AccessMask<E> {
+    /// Returns an `AccessMask` allowing `allow` and denying `deny`.
+    pub fn new(allow: EnumSet<E>, deny: EnumSet<E>) -> Self {
+        AccessMask { allow, deny }
+    }
+
+    /// Returns `base` with the allowed elements added and the denied elements
+    /// removed.
+    pub fn apply(&self, base: EnumSet<E>) -> EnumSet<E> {
+        let allow = EnumSet::new_with_bits(self.allow.bits);
+        let deny = EnumSet::new_with_bits(self.deny.bits);
+        EnumSet::resolve(allow, deny, base)
+    }
+
+    /// Combines the mask with `other`, allowing and denying the elements
+    /// allowed and denied by either of them.
+    pub fn merge(&self, other: &Self) -> Self {
+        AccessMask {
+            allow: EnumSet::new_with_bits(self.allow.bits | other.allow.bits),
+            deny: EnumSet::new_with_bits(self.deny.bits | other.deny.bits),
+        }
+    }
+}
+
+impl<E: CLike> Default for AccessMask<E> {
+    fn default() -> Self {
+        AccessMask { allow: EnumSet::new(), deny: EnumSet::new() }
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for AccessMask<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("AccessMask")
+           .field("allow", &self.allow)
+           .field("deny", &self.deny)
+           .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::set;
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::AccessMask;
+
+    #[test]
+    fn test_apply() {
+        let mask = AccessMask::new(set(&[B, C]), set(&[C]));
+        assert_eq!(set(&[A, B]), mask.apply(set(&[A])));
+        assert_eq!(EnumSet::resolve(mask.allow, mask.deny, set(&[A])), mask.apply(set(&[A])));
+        assert_eq!(set(&[A]), AccessMask::default().apply(set(&[A])));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mask = AccessMask::new(set(&[A]), EnumSet::new())
+            .merge(&AccessMask::new(set(&[B]), set(&[A])));
+        assert_eq!(set(&[A, B]), mask.allow);
+        assert_eq!(set(&[A]), mask.deny);
+        assert_eq!(set(&[B]), mask.apply(EnumSet::new()));
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Combinatorial algorithms over sets.
+
+use std::collections::VecDeque;
+
+use {CLike, EnumSet};
+
+/// The largest target size for which `set_cover` searches for an exact
+/// solution. The search needs a table with `2^len` entries.
+pub const EXACT_COVER_LIMIT: u32 = 20;
+
+/// Returns the positions set in `bits` among the bits of `elements`, so
+/// that the `i`th lowest bit of `elements` maps to position `i`.
+fn gather(bits: u32, mut elements: u32) -> u32 {
+    let mut positions = 0;
+    let mut position = 0;
+    while elements != 0 {
+        let lowest = elements & elements.wrapping_neg();
+        if bits & lowest != 0 {
+            positions |= 1 << position;
+        }
+        elements &= elements - 1;
+        position += 1;
+    }
+    positions
+}
+
+/// Finds a smallest cover of the full mask of `len` positions by a
+/// breadth-first search over the masks covered so far.
+fn exact_cover(len: u32, candidates: &[u32]) -> Option<Vec<usize>> {
+    let full = ((1u64 << len) - 1) as u32;
+    // `via[mask]` is one more than the candidate last added to reach
+    // `mask`, or 0 if `mask` has not been reached yet.
+    let mut via = vec![0u32; 1 << len];
+    let mut from = vec![0u32; 1 << len];
+    let mut queue = VecDeque::new();
+    queue.push_back(0);
+    while let Some(mask) = queue.pop_front() {
+        if mask == full {
+            let mut selection = Vec::new();
+            let mut mask = mask;
+            while mask != 0 {
+                selection.push(via[mask as usize] as usize - 1);
+                mask = from[mask as usize];
+            }
+            selection.sort_unstable();
+            return Some(selection);
+        }
+        for (index, &candidate) in candidates.iter().enumerate() {
+            let next = mask | candidate;
+            if next != 0 && via[next as usize] == 0 {
+                via[next as usize] = index as u32 + 1;
+                from[next as usize] = mask;
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Covers `target` by repeatedly picking the candidate covering the most
+/// elements not covered yet.
+fn greedy_cover(target: u32, candidates: &[u32]) -> Option<Vec<usize>> {
+    let mut uncovered = target;
+    let mut selection = Vec::new();
+    while uncovered != 0 {
+        let (index, gain) = candidates.iter()
+                                      .map(|&candidate| (candidate & uncovered).count_ones())
+                                      .enumerate()
+                                      .max_by_key(|&(index, gain)| (gain, !index))?;
+        if gain == 0 {
+            return None;
+        }
+        uncovered &= !candidates[index];
+        selection.push(index);
+    }
+    selection.sort_unstable();
+    Some(selection)
+}
+
+/// Returns the indices of a selection of `candidates` whose union contains
+/// `target`, in ascending order, or `None` if the union of every candidate
+/// does not contain it.
+///
+/// The selection is as small as possible when `target` has at most
+/// `EXACT_COVER_LIMIT` elements. Larger targets are covered greedily, which
+/// may select more candidates than necessary.
+///
+/// ```
+/// use enum_set::{CLike, EnumSet};
+/// use enum_set::algorithms::set_cover;
+/// use std::mem;
+///
+/// #[derive(Clone, Copy)]
+/// #[repr(u32)]
+/// enum Capability {
+///     Storage, Compute, Network
+/// }
+///
+/// impl CLike for Capability {
+///     fn to_u32(&self) -> u32 {
+///         *self as u32
+///     }
+///
+///     unsafe fn from_u32(v: u32) -> Capability {
+///         mem::transmute(v)
+///     }
+/// }
+///
+/// let services: Vec<EnumSet<Capability>> = vec![
+///     [Capability::Storage].iter().collect(),
+///     [Capability::Compute].iter().collect(),
+///     [Capability::Compute, Capability::Network].iter().collect(),
+/// ];
+/// let required = [Capability::Storage, Capability::Network].iter().collect();
+/// assert_eq!(Some(vec![0, 2]), set_cover(required, &services));
+/// ```
+pub fn set_cover<E: CLike>(target: EnumSet<E>, candidates: &[EnumSet<E>]) -> Option<Vec<usize>> {
+    let len = target.bits.count_ones();
+    if len <= EXACT_COVER_LIMIT {
+        let candidates: Vec<u32> = candidates.iter()
+                                             .map(|candidate| gather(candidate.bits, target.bits))
+                                             .collect();
+        exact_cover(len, &candidates)
+    } else {
+        let candidates: Vec<u32> = candidates.iter().map(|candidate| candidate.bits).collect();
+        greedy_cover(target.bits, &candidates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::set;
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::{exact_cover, gather, greedy_cover, set_cover};
+
+    #[test]
+    fn test_gather() {
+        assert_eq!(0b101, gather(0b1_0001, 0b1_0101));
+        assert_eq!(0, gather(0b1010, 0b0101));
+    }
+
+    #[test]
+    fn test_set_cover() {
+        let candidates = [set(&[A]), set(&[A, B]), set(&[B, C]), set(&[C])];
+        let selection = set_cover(EnumSet::all(), &candidates).unwrap();
+        assert_eq!(2, selection.len());
+        assert_eq!(EnumSet::all(), selection.iter().fold(EnumSet::new(), |acc, &i| acc | candidates[i]));
+        assert_eq!(Some(vec![2]), set_cover(set(&[C, B]), &candidates));
+        assert_eq!(Some(vec![]), set_cover(EnumSet::new(), &candidates));
+        assert_eq!(None, set_cover(set(&[C]), &candidates[..2]));
+        assert_eq!(None, set_cover(set(&[A]), &[]));
+    }
+
+    #[test]
+    fn test_exact_beats_greedy() {
+        // Greedy takes the largest set first and then needs both halves.
+        let candidates = [0b00_0111, 0b11_1000, 0b01_1011];
+        assert_eq!(Some(vec![0, 1, 2]), greedy_cover(0b11_1111, &candidates));
+        assert_eq!(Some(vec![0, 1]), exact_cover(6, &candidates));
+    }
+}
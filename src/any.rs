@@ -0,0 +1,181 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::any::{self, TypeId};
+use std::fmt;
+
+use {names, EnumSet, Finite};
+
+#[derive(Clone, PartialEq, Eq)]
+/// A set of any enum, with the enum type erased.
+///
+/// The set keeps the names of the variants, taken from their `Debug`
+/// representations, so elements can be inspected and changed by name. It can
+/// be converted back to an `EnumSet` of the original enum with `downcast`.
+///
+/// ```
+/// use enum_set::{AnyEnumSet, CLike, EnumSet, Finite};
+/// use std::mem;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u32)]
+/// enum Hook {
+///     Load, Unload
+/// }
+///
+/// impl CLike for Hook {
+///     fn to_u32(&self) -> u32 {
+///         *self as u32
+///     }
+///
+///     unsafe fn from_u32(v: u32) -> Hook {
+///         mem::transmute(v)
+///     }
+/// }
+///
+/// unsafe impl Finite for Hook {
+///     const VARIANT_COUNT: u32 = 2;
+/// }
+///
+/// let mut hooks = AnyEnumSet::from(EnumSet::<Hook>::new());
+/// assert!(hooks.insert("Unload"));
+/// assert!(hooks.contains("Unload"));
+/// assert_eq!(Some(vec![Hook::Unload]),
+///            hooks.downcast::<Hook>().map(|set| set.iter().collect()));
+/// ```
+pub struct AnyEnumSet {
+    bits: u32,
+    type_id: TypeId,
+    type_name: &'static str,
+    names: Vec<String>,
+}
+
+impl AnyEnumSet {
+    /// Returns `true` if the set contains the variant called `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.index_of(name).is_some_and(|index| self.bits & (1 << index) != 0)
+    }
+
+    /// Adds the variant called `name` to the set.
+    ///
+    /// Returns `true` if `name` is a variant which was not already present
+    /// in the set.
+    pub fn insert(&mut self, name: &str) -> bool {
+        match self.index_of(name) {
+            Some(index) => {
+                let result = self.bits & (1 << index) == 0;
+                self.bits |= 1 << index;
+                result
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the variant called `name` from the set.
+    ///
+    /// Returns `true` if the variant was present in the set.
+    pub fn remove(&mut self, name: &str) -> bool {
+        match self.index_of(name) {
+            Some(index) => {
+                let result = self.bits & (1 << index) != 0;
+                self.bits &= !(1 << index);
+                result
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the names of every variant of the enum.
+    pub fn variant_names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|name| &**name)
+    }
+
+    /// Returns the names of the set's elements.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        let bits = self.bits;
+        self.variant_names().enumerate()
+                            .filter(move |&(index, _)| bits & (1 << index) != 0)
+                            .map(|(_, name)| name)
+    }
+
+    /// Returns the name of the enum type, as given by `std::any::type_name`.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns `true` if the set holds variants of `E`.
+    pub fn is<E: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<E>()
+    }
+
+    /// Returns the set as an `EnumSet<E>`, or `None` if it holds variants of
+    /// another enum.
+    pub fn downcast<E: Finite + 'static>(&self) -> Option<EnumSet<E>> {
+        if self.is::<E>() {
+            Some(EnumSet::new_with_bits(self.bits))
+        } else {
+            None
+        }
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|variant| variant == name)
+    }
+}
+
+impl<E: Finite + fmt::Debug + 'static> From<EnumSet<E>> for AnyEnumSet {
+    fn from(set: EnumSet<E>) -> Self {
+        AnyEnumSet {
+            bits: set.bits,
+            type_id: TypeId::of::<E>(),
+            type_name: any::type_name::<E>(),
+            names: names::variants::<E>().map(|(_, name)| name).collect(),
+        }
+    }
+}
+
+impl fmt::Debug for AnyEnumSet {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use super::AnyEnumSet;
+
+    #[test]
+    fn test_by_name() {
+        let mut e = AnyEnumSet::from(set(&[A]));
+        assert!(e.contains("A"));
+        assert!(!e.contains("B"));
+        assert!(!e.contains("D"));
+        assert!(e.insert("C"));
+        assert!(!e.insert("C"));
+        assert!(!e.insert("D"));
+        assert!(e.remove("A"));
+        assert!(!e.remove("A"));
+        assert_eq!(vec!["C"], e.iter().collect::<Vec<_>>());
+        assert_eq!(vec!["A", "B", "C"], e.variant_names().collect::<Vec<_>>());
+        assert_eq!("{\"C\"}", format!("{:?}", e));
+    }
+
+    #[test]
+    fn test_downcast() {
+        let e = AnyEnumSet::from(set(&[A, B]));
+        assert!(e.is::<Foo>());
+        assert!(!e.is::<u32>());
+        assert_eq!(Some(set(&[A, B])), e.downcast::<Foo>());
+        assert!(e.type_name().ends_with("Foo"));
+    }
+}
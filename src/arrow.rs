@@ -0,0 +1,182 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions between columns of sets and Apache Arrow arrays.
+//!
+//! A column can be represented either as a `UInt32Array` of bits, or as a
+//! `ListArray` of variant names, dictionary-encoded with `UInt8` keys. Names
+//! are the `Debug` representations of the variants in `Finite::NAME_CASE`.
+
+use std::error;
+use std::fmt;
+
+use arrow_array::builder::{ListBuilder, StringDictionaryBuilder};
+use arrow_array::cast::AsArray;
+use arrow_array::types::UInt8Type;
+use arrow_array::{Array, ListArray, UInt32Array};
+
+use {names, universe, CLike, EnumSet, Finite};
+
+/// An error returned when converting an Arrow array to sets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FromArrowError {
+    /// The array has a null at `index`.
+    Null {
+        /// The index of the null.
+        index: usize,
+    },
+    /// The bits at `index` do not correspond to variants.
+    InvalidBits {
+        /// The index of the bits.
+        index: usize,
+        /// The invalid bits.
+        bits: u32,
+    },
+    /// The list at `index` contains a name which is not a variant.
+    UnknownName {
+        /// The index of the list.
+        index: usize,
+        /// The unknown name.
+        name: String,
+    },
+    /// The elements of the lists are not strings.
+    UnsupportedType,
+}
+
+impl fmt::Display for FromArrowError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FromArrowError::Null { index } => write!(fmt, "unexpected null at index {}", index),
+            FromArrowError::InvalidBits { index, bits } => {
+                write!(fmt, "invalid bits {:#x} at index {}", bits, index)
+            }
+            FromArrowError::UnknownName { index, ref name } => {
+                write!(fmt, "unknown variant `{}` at index {}", name, index)
+            }
+            FromArrowError::UnsupportedType => fmt.write_str("list elements are not strings"),
+        }
+    }
+}
+
+impl error::Error for FromArrowError {}
+
+/// Returns an array holding the bits of each set.
+pub fn to_mask_array<E: CLike>(sets: &[EnumSet<E>]) -> UInt32Array {
+    sets.iter().map(|set| set.bits).collect::<Vec<_>>().into()
+}
+
+/// Returns the sets whose bits are held by `array`.
+pub fn from_mask_array<E: Finite>(array: &UInt32Array) -> Result<Vec<EnumSet<E>>, FromArrowError> {
+    array.iter()
+         .enumerate()
+         .map(|(index, bits)| match bits {
+             Some(bits) if bits & !universe::<E>() == 0 => Ok(EnumSet::new_with_bits(bits)),
+             Some(bits) => Err(FromArrowError::InvalidBits { index, bits }),
+             None => Err(FromArrowError::Null { index }),
+         })
+         .collect()
+}
+
+/// Returns an array holding the names of the elements of each set.
+pub fn to_name_array<E: Finite + fmt::Debug>(sets: &[EnumSet<E>]) -> ListArray {
+    let mut builder = ListBuilder::new(StringDictionaryBuilder::<UInt8Type>::new());
+    for set in sets {
+        for element in set.iter() {
+            builder.values().append_value(names::name(&element));
+        }
+        builder.append(true);
+    }
+    builder.finish()
+}
+
+/// Returns the sets whose element names are held by `array`.
+///
+/// The elements of the lists can be strings or strings dictionary-encoded
+/// with `UInt8` keys.
+pub fn from_name_array<E: Finite + fmt::Debug>(array: &ListArray)
+                                                -> Result<Vec<EnumSet<E>>, FromArrowError> {
+    let mut sets = Vec::with_capacity(array.len());
+    for index in 0..array.len() {
+        if array.is_null(index) {
+            return Err(FromArrowError::Null { index });
+        }
+        let values = array.value(index);
+        let names: Vec<Option<&str>> = if let Some(dictionary) =
+            values.as_dictionary_opt::<UInt8Type>() {
+            let strings = dictionary.values()
+                                    .as_string_opt::<i32>()
+                                    .ok_or(FromArrowError::UnsupportedType)?;
+            dictionary.keys()
+                      .iter()
+                      .map(|key| key.map(|key| strings.value(key as usize)))
+                      .collect()
+        } else if let Some(strings) = values.as_string_opt::<i32>() {
+            strings.iter().collect()
+        } else {
+            return Err(FromArrowError::UnsupportedType);
+        };
+
+        let elements = names.into_iter().map(|name| name.ok_or(FromArrowError::Null { index }));
+        sets.push(names::collect(elements, |name| {
+            Err(FromArrowError::UnknownName { index, name: name.to_owned() })
+        })?);
+    }
+    Ok(sets)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::builder::{ListBuilder, StringBuilder};
+    use arrow_array::{Array, ListArray, UInt32Array};
+    use arrow_array::types::Int32Type;
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use super::{from_mask_array, from_name_array, to_mask_array, to_name_array, FromArrowError};
+
+    #[test]
+    fn test_masks() {
+        let sets = vec![set(&[A, C]), set(&[]), set(&[B])];
+        let array = to_mask_array(&sets);
+        assert_eq!(&[0b101, 0, 0b10], array.values().as_ref());
+        assert_eq!(Ok(sets), from_mask_array(&array));
+
+        let array = UInt32Array::from(vec![Some(1), Some(0b1000)]);
+        assert_eq!(Err(FromArrowError::InvalidBits { index: 1, bits: 0b1000 }),
+                   from_mask_array::<Foo>(&array));
+        let array = UInt32Array::from(vec![None, Some(1)]);
+        assert_eq!(Err(FromArrowError::Null { index: 0 }), from_mask_array::<Foo>(&array));
+    }
+
+    #[test]
+    fn test_names() {
+        let sets = vec![set(&[A, C]), set(&[]), set(&[B, C])];
+        let array = to_name_array(&sets);
+        assert_eq!(3, array.len());
+        assert_eq!(2, array.value(0).len());
+        assert_eq!(Ok(sets), from_name_array(&array));
+    }
+
+    #[test]
+    fn test_plain_names() {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        builder.values().append_value("B");
+        builder.append(true);
+        builder.values().append_value("D");
+        builder.append(true);
+        let array = builder.finish();
+        assert_eq!(Err(FromArrowError::UnknownName { index: 1, name: "D".to_owned() }),
+                   from_name_array::<Foo>(&array));
+
+        let array = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![Some(vec![Some(0)])]);
+        assert_eq!(Err(FromArrowError::UnsupportedType), from_name_array::<Foo>(&array));
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Operations over slices of sets.
+//!
+//! These functions work directly on the underlying bit masks with simple
+//! loops, which the compiler is able to auto-vectorize.
+
+use {bit, CLike, EnumSet};
+
+/// Adds every element of `src[i]` to `dst[i]`, for each `i`.
+///
+/// # Panics
+///
+/// Panics if `dst` and `src` have different lengths.
+pub fn union_assign<E: CLike>(dst: &mut [EnumSet<E>], src: &[EnumSet<E>]) {
+    assert_eq!(dst.len(), src.len(), "slices must have the same length");
+    for (d, s) in dst.iter_mut().zip(src) {
+        d.bits |= s.bits;
+    }
+}
+
+/// Returns the intersection of all sets in `sets`.
+///
+/// Returns an empty set if `sets` is empty.
+pub fn intersect_all<E: CLike>(sets: &[EnumSet<E>]) -> EnumSet<E> {
+    if sets.is_empty() {
+        return EnumSet::new();
+    }
+    EnumSet::new_with_bits(sets.iter().fold(!0, |acc, s| acc & s.bits))
+}
+
+/// Returns the number of sets in `sets` which contain `value`.
+pub fn count_containing<E: CLike>(sets: &[EnumSet<E>], value: E) -> usize {
+    let mask = bit(&value);
+    sets.iter().map(|s| ((s.bits & mask) != 0) as usize).sum()
+}
+
+/// Returns the indices of the sets in `sets` which contain every element of
+/// `include` and no element of `exclude`, in increasing order.
+pub fn matching_indices<'a, E: CLike>(sets: &'a [EnumSet<E>],
+                                      include: EnumSet<E>,
+                                      exclude: EnumSet<E>)
+                                      -> impl Iterator<Item = usize> + 'a {
+    let (include, exclude) = (include.bits, exclude.bits);
+    sets.iter()
+        .enumerate()
+        .filter(move |&(_, s)| (s.bits & include) == include && (s.bits & exclude) == 0)
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::set;
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::{count_containing, intersect_all, matching_indices, union_assign};
+
+    #[test]
+    fn test_union_assign() {
+        let mut dst = vec![EnumSet::new(); 2];
+        dst[0].insert(A);
+        let mut src = vec![EnumSet::new(); 2];
+        src[0].insert(B);
+        src[1].insert(C);
+
+        union_assign(&mut dst, &src);
+        assert_eq!(vec![A, B], dst[0].iter().collect::<Vec<_>>());
+        assert_eq!(vec![C], dst[1].iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_union_assign_length_mismatch() {
+        let mut dst = vec![EnumSet::<::tests::Foo>::new(); 2];
+        union_assign(&mut dst, &[EnumSet::new()]);
+    }
+
+    #[test]
+    fn test_intersect_all() {
+        let mut e1 = EnumSet::new();
+        e1.insert(A);
+        e1.insert(B);
+        let mut e2 = EnumSet::new();
+        e2.insert(B);
+        e2.insert(C);
+
+        assert_eq!(vec![B], intersect_all(&[e1, e2]).iter().collect::<Vec<_>>());
+        assert!(intersect_all::<::tests::Foo>(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_count_containing() {
+        let mut e1 = EnumSet::new();
+        e1.insert(A);
+        let mut e2 = EnumSet::new();
+        e2.insert(A);
+        e2.insert(B);
+
+        let sets = [e1, e2, EnumSet::new()];
+        assert_eq!(2, count_containing(&sets, A));
+        assert_eq!(1, count_containing(&sets, B));
+        assert_eq!(0, count_containing(&sets, C));
+    }
+
+    #[test]
+    fn test_matching_indices() {
+        let sets = [set(&[A, B]), set(&[A]), set(&[A, B, C]), set(&[B]), set(&[A, B])];
+
+        let matching: Vec<_> = matching_indices(&sets, set(&[A, B]), set(&[C])).collect();
+        assert_eq!(vec![0, 4], matching);
+
+        let matching: Vec<_> = matching_indices(&sets, set(&[]), set(&[])).collect();
+        assert_eq!(vec![0, 1, 2, 3, 4], matching);
+
+        assert_eq!(0, matching_indices(&sets, set(&[C]), set(&[A])).count());
+    }
+}
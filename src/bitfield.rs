@@ -0,0 +1,57 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Use of sets as `modular-bitfield` fields.
+//!
+//! A set occupies one bit per variant, so an `EnumSet<E>` field of a
+//! `#[bitfield]` struct is `E::VARIANT_COUNT` bits wide.
+
+use modular_bitfield::error::{InvalidBitPattern, OutOfBounds};
+use modular_bitfield::Specifier;
+
+use {universe, EnumSet, Finite};
+
+impl<E: Finite> Specifier for EnumSet<E> {
+    const BITS: usize = E::VARIANT_COUNT as usize;
+
+    type Bytes = u32;
+    type InOut = Self;
+
+    fn into_bytes(input: Self) -> Result<u32, OutOfBounds> {
+        Ok(input.bits)
+    }
+
+    fn from_bytes(bytes: u32) -> Result<Self, InvalidBitPattern<u32>> {
+        if bytes & !universe::<E>() == 0 {
+            Ok(EnumSet::new_with_bits(bytes))
+        } else {
+            Err(InvalidBitPattern::new(bytes))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use modular_bitfield::Specifier;
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_specifier() {
+        assert_eq!(3, <EnumSet<Foo> as Specifier>::BITS);
+        assert_eq!(Ok(0b101), <EnumSet<Foo> as Specifier>::into_bytes(set(&[A, C])).map_err(|_| ()));
+        assert_eq!(Ok(set(&[B])), <EnumSet<Foo> as Specifier>::from_bytes(0b10).map_err(|_| ()));
+        assert_eq!(Err(0b1010),
+                   <EnumSet<Foo> as Specifier>::from_bytes(0b1010).map_err(|e| e.invalid_bytes()));
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A short, URL-safe string encoding of sets.
+//!
+//! An encoded set consists of a version digit, the bit mask written in base
+//! 32 (most significant digit first) and a checksum digit. Digits use the
+//! lowercase alphabet `0123456789abcdefghjkmnpqrstvwxyz`, which avoids the
+//! easily confused letters `i`, `l`, `o` and `u`.
+
+use std::error;
+use std::fmt;
+
+use {universe, CLike, EnumSet, Finite};
+
+const ALPHABET: &[u8; 32] = b"0123456789abcdefghjkmnpqrstvwxyz";
+
+const VERSION: u32 = 1;
+
+/// An error returned when decoding a set from a compact string fails.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CompactStringError {
+    /// The string is too short to hold a version, a mask and a checksum.
+    InvalidLength,
+    /// The string contains a character outside of the encoding alphabet.
+    InvalidCharacter,
+    /// The string was produced by an unsupported version of the encoding.
+    UnsupportedVersion,
+    /// The checksum does not match the encoded mask.
+    ChecksumMismatch,
+    /// The encoded mask does not fit in 32 bits.
+    Overflow,
+    /// The encoded mask contains bits which do not correspond to a variant.
+    UnknownVariant,
+}
+
+impl fmt::Display for CompactStringError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let description = match *self {
+            CompactStringError::InvalidLength => "invalid length",
+            CompactStringError::InvalidCharacter => "invalid character",
+            CompactStringError::UnsupportedVersion => "unsupported version",
+            CompactStringError::ChecksumMismatch => "checksum mismatch",
+            CompactStringError::Overflow => "mask does not fit in 32 bits",
+            CompactStringError::UnknownVariant => "mask contains an unknown variant",
+        };
+        write!(fmt, "invalid compact set string: {}", description)
+    }
+}
+
+impl error::Error for CompactStringError {}
+
+fn digit_value(c: u8) -> Option<u32> {
+    let c = c.to_ascii_lowercase();
+    ALPHABET.iter().position(|&d| d == c).map(|position| position as u32)
+}
+
+fn checksum(digits: &[u32]) -> u32 {
+    // Odd weights are coprime to 32, so any single changed digit is detected.
+    digits.iter().enumerate().fold(VERSION, |acc, (i, &digit)| {
+        (acc + (2 * i as u32 + 1) * digit) % 32
+    })
+}
+
+fn encode(mut bits: u32) -> String {
+    let mut digits = Vec::with_capacity(7);
+    loop {
+        digits.push(bits % 32);
+        bits /= 32;
+        if bits == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+
+    let mut result = String::with_capacity(digits.len() + 2);
+    result.push(ALPHABET[VERSION as usize] as char);
+    for &digit in &digits {
+        result.push(ALPHABET[digit as usize] as char);
+    }
+    result.push(ALPHABET[checksum(&digits) as usize] as char);
+    result
+}
+
+fn decode(s: &str) -> Result<u32, CompactStringError> {
+    let digits = s.bytes()
+                  .map(|c| digit_value(c).ok_or(CompactStringError::InvalidCharacter))
+                  .collect::<Result<Vec<_>, _>>()?;
+    if digits.len() < 3 {
+        return Err(CompactStringError::InvalidLength);
+    }
+    if digits[0] != VERSION {
+        return Err(CompactStringError::UnsupportedVersion);
+    }
+    let mask_digits = &digits[1..digits.len() - 1];
+    if checksum(mask_digits) != digits[digits.len() - 1] {
+        return Err(CompactStringError::ChecksumMismatch);
+    }
+
+    let mut bits: u32 = 0;
+    for &digit in mask_digits {
+        bits = bits.checked_mul(32)
+                   .and_then(|bits| bits.checked_add(digit))
+                   .ok_or(CompactStringError::Overflow)?;
+    }
+    Ok(bits)
+}
+
+impl<E: CLike> EnumSet<E> {
+    /// Encodes the set as a short, URL-safe string.
+    ///
+    /// The result can be decoded with `from_compact_string`.
+    pub fn to_compact_string(&self) -> String {
+        encode(self.bits)
+    }
+}
+
+impl<E: Finite> EnumSet<E> {
+    /// The maximum length of the strings produced by `to_compact_string`:
+    /// the version and checksum digits plus one digit per five variants.
+    ///
+    /// Every character is ASCII, so this is also the maximum length in
+    /// bytes.
+    pub const MAX_COMPACT_STRING_LEN: usize = if E::VARIANT_COUNT == 0 {
+        3
+    } else {
+        2 + (E::VARIANT_COUNT as usize).div_ceil(5)
+    };
+
+    /// Decodes a set encoded by `to_compact_string`.
+    ///
+    /// Decoding is case insensitive.
+    pub fn from_compact_string(s: &str) -> Result<Self, CompactStringError> {
+        let bits = decode(s)?;
+        if bits & !universe::<E>() != 0 {
+            return Err(CompactStringError::UnknownVariant);
+        }
+        Ok(Self::new_with_bits(bits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::Foo;
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::CompactStringError;
+
+    #[test]
+    fn test_round_trip() {
+        let mut e: EnumSet<Foo> = EnumSet::new();
+        assert_eq!(Ok(e), EnumSet::from_compact_string(&e.to_compact_string()));
+        e.insert(A);
+        e.insert(C);
+        let encoded = e.to_compact_string();
+        assert_eq!(3, encoded.len());
+        assert_eq!(Ok(e), EnumSet::from_compact_string(&encoded));
+        assert_eq!(Ok(e), EnumSet::from_compact_string(&encoded.to_uppercase()));
+    }
+
+    #[test]
+    fn test_max_len() {
+        assert_eq!(3, EnumSet::<Foo>::MAX_COMPACT_STRING_LEN);
+        assert_eq!(EnumSet::<Foo>::MAX_COMPACT_STRING_LEN,
+                   EnumSet::<Foo>::all().to_compact_string().len());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut e: EnumSet<Foo> = EnumSet::new();
+        e.insert(B);
+        let encoded = e.to_compact_string();
+
+        assert_eq!(Err(CompactStringError::InvalidLength),
+                   EnumSet::<Foo>::from_compact_string(&encoded[..2]));
+        assert_eq!(Err(CompactStringError::InvalidCharacter),
+                   EnumSet::<Foo>::from_compact_string("1-2"));
+        assert_eq!(Err(CompactStringError::UnsupportedVersion),
+                   EnumSet::<Foo>::from_compact_string(&encoded.replacen('1', "2", 1)));
+        assert_eq!(Err(CompactStringError::ChecksumMismatch),
+                   EnumSet::<Foo>::from_compact_string(&format!("{}1", &encoded[..2])));
+
+        let mut unknown = encoded[..1].to_owned();
+        unknown.push('8');
+        unknown.push(super::ALPHABET[super::checksum(&[8]) as usize] as char);
+        assert_eq!(Err(CompactStringError::UnknownVariant),
+                   EnumSet::<Foo>::from_compact_string(&unknown));
+
+        let mut overflow = encoded[..1].to_owned();
+        let digits = [4, 0, 0, 0, 0, 0, 0];
+        overflow.extend(digits.iter().map(|&d| super::ALPHABET[d as usize] as char));
+        overflow.push(super::ALPHABET[super::checksum(&digits) as usize] as char);
+        assert_eq!(Err(CompactStringError::Overflow),
+                   EnumSet::<Foo>::from_compact_string(&overflow));
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Validation of sets against groups of exclusive or dependent variants.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use {CLike, EnumSet};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Kind {
+    Exclusive,
+    Together,
+}
+
+/// A list of rules restricting which combinations of variants a set may
+/// contain.
+///
+/// Rules are added with `exclusive` and `together`, and checked with
+/// `validate`.
+pub struct Constraints<E> {
+    groups: Vec<(Kind, u32)>,
+    phantom: PhantomData<E>,
+}
+
+/// A rule of a `Constraints` which a set does not satisfy.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum ConstraintViolation<E> {
+    /// The set contains more than one variant of an exclusive group.
+    Exclusive {
+        /// The exclusive group.
+        group: EnumSet<E>,
+        /// The variants of the group in the set.
+        present: EnumSet<E>,
+    },
+    /// The set contains some, but not all, variants of a group which must be
+    /// used together.
+    Together {
+        /// The group of variants which must be used together.
+        group: EnumSet<E>,
+        /// The variants of the group missing from the set.
+        missing: EnumSet<E>,
+    },
+}
+
+impl<E: CLike> Constraints<E> {
+    /// Returns a `Constraints` without any rules.
+    pub fn new() -> Self {
+        Constraints { groups: Vec::new(), phantom: PhantomData }
+    }
+
+    /// Adds a rule allowing at most one variant of `group` in a set.
+    pub fn exclusive(mut self, group: EnumSet<E>) -> Self {
+        self.groups.push((Kind::Exclusive, group.bits));
+        self
+    }
+
+    /// Adds a rule requiring a set to contain either all or none of the
+    /// variants of `group`.
+    pub fn together(mut self, group: EnumSet<E>) -> Self {
+        self.groups.push((Kind::Together, group.bits));
+        self
+    }
+
+    /// Returns the rules `set` does not satisfy, in the order they were
+    /// added.
+    pub fn violations(&self, set: &EnumSet<E>) -> Vec<ConstraintViolation<E>> {
+        self.groups.iter().filter_map(|&(kind, group)| {
+            let present = set.bits & group;
+            match kind {
+                Kind::Exclusive if present.count_ones() > 1 => {
+                    Some(ConstraintViolation::Exclusive {
+                        group: EnumSet::new_with_bits(group),
+                        present: EnumSet::new_with_bits(present),
+                    })
+                }
+                Kind::Together if present != 0 && present != group => {
+                    Some(ConstraintViolation::Together {
+                        group: EnumSet::new_with_bits(group),
+                        missing: EnumSet::new_with_bits(group & !present),
+                    })
+                }
+                _ => None,
+            }
+        }).collect()
+    }
+
+    /// Checks `set` against every rule, returning the violated ones if there
+    /// are any.
+    pub fn validate(&self, set: &EnumSet<E>) -> Result<(), Vec<ConstraintViolation<E>>> {
+        let violations = self.violations(set);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+impl<E: CLike> Default for Constraints<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for ConstraintViolation<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConstraintViolation::Exclusive { ref group, ref present } => {
+                fmt.debug_struct("Exclusive")
+                   .field("group", group)
+                   .field("present", present)
+                   .finish()
+            }
+            ConstraintViolation::Together { ref group, ref missing } => {
+                fmt.debug_struct("Together")
+                   .field("group", group)
+                   .field("missing", missing)
+                   .finish()
+            }
+        }
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Display for ConstraintViolation<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConstraintViolation::Exclusive { ref present, .. } => {
+                write!(fmt, "{:?} are mutually exclusive", present)
+            }
+            ConstraintViolation::Together { ref group, ref missing } => {
+                write!(fmt, "{:?} must be used together, missing {:?}", group, missing)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::set;
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::{ConstraintViolation, Constraints};
+
+    #[test]
+    fn test_exclusive() {
+        let constraints = Constraints::new().exclusive(set(&[A, B]));
+        assert_eq!(Ok(()), constraints.validate(&set(&[A, C])));
+        assert_eq!(Ok(()), constraints.validate(&EnumSet::new()));
+        assert_eq!(Err(vec![ConstraintViolation::Exclusive {
+                       group: set(&[A, B]),
+                       present: set(&[A, B]),
+                   }]),
+                   constraints.validate(&EnumSet::all()));
+    }
+
+    #[test]
+    fn test_together() {
+        let constraints = Constraints::new().together(set(&[B, C]));
+        assert_eq!(Ok(()), constraints.validate(&set(&[A])));
+        assert_eq!(Ok(()), constraints.validate(&set(&[B, C])));
+        let violations = constraints.violations(&set(&[B]));
+        assert_eq!(vec![ConstraintViolation::Together { group: set(&[B, C]), missing: set(&[C]) }],
+                   violations);
+        assert_eq!("{B, C} must be used together, missing {C}", violations[0].to_string());
+    }
+
+    #[test]
+    fn test_multiple_violations() {
+        let constraints = Constraints::new().together(set(&[A, C])).exclusive(set(&[B, C]));
+        assert_eq!(2, constraints.violations(&set(&[B, C])).len());
+        assert_eq!(Ok(()), constraints.validate(&set(&[A, C])));
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::borrow;
+use std::iter;
+use std::marker::PhantomData;
+
+use {bit, CLike, EnumSet};
+
+/// A count of how often pairs of variants appear together in a collection of
+/// sets.
+pub struct CooccurrenceMatrix<E> {
+    // `counts[32 * a + b]` is the number of sets containing both `a` and `b`.
+    counts: Vec<u64>,
+    sets: u64,
+    phantom: PhantomData<E>,
+}
+
+fn index<E: CLike>(value: &E) -> usize {
+    bit(value).trailing_zeros() as usize
+}
+
+impl<E: CLike> CooccurrenceMatrix<E> {
+    /// Returns a `CooccurrenceMatrix` without any sets.
+    pub fn new() -> Self {
+        CooccurrenceMatrix { counts: vec![0; 32 * 32], sets: 0, phantom: PhantomData }
+    }
+
+    /// Counts the pairs of elements in `set`.
+    pub fn add(&mut self, set: &EnumSet<E>) {
+        let mut rows = set.bits;
+        while rows != 0 {
+            let a = rows.trailing_zeros() as usize;
+            rows &= rows - 1;
+            let mut columns = set.bits;
+            while columns != 0 {
+                let b = columns.trailing_zeros() as usize;
+                columns &= columns - 1;
+                self.counts[32 * a + b] += 1;
+            }
+        }
+        self.sets += 1;
+    }
+
+    /// Returns the number of sets which were added.
+    pub fn sets(&self) -> u64 {
+        self.sets
+    }
+
+    /// Returns the number of sets containing both `a` and `b`.
+    ///
+    /// If `a` and `b` are equal, this is the number of sets containing `a`.
+    pub fn count<A: borrow::Borrow<E>, B: borrow::Borrow<E>>(&self, a: A, b: B) -> u64 {
+        self.counts[32 * index(a.borrow()) + index(b.borrow())]
+    }
+}
+
+impl<E: CLike> Default for CooccurrenceMatrix<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: CLike> iter::FromIterator<EnumSet<E>> for CooccurrenceMatrix<E> {
+    fn from_iter<I: IntoIterator<Item = EnumSet<E>>>(iterator: I) -> Self {
+        let mut ret = Self::new();
+        ret.extend(iterator);
+        ret
+    }
+}
+
+impl<E: CLike> Extend<EnumSet<E>> for CooccurrenceMatrix<E> {
+    fn extend<I: IntoIterator<Item = EnumSet<E>>>(&mut self, iter: I) {
+        for set in iter {
+            self.add(&set);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use super::CooccurrenceMatrix;
+
+    #[test]
+    fn test_counts() {
+        let matrix: CooccurrenceMatrix<Foo> =
+            vec![set(&[A, B]), set(&[A, B, C]), set(&[C]), set(&[])].into_iter().collect();
+
+        assert_eq!(4, matrix.sets());
+        assert_eq!(2, matrix.count(A, A));
+        assert_eq!(2, matrix.count(A, B));
+        assert_eq!(2, matrix.count(B, A));
+        assert_eq!(1, matrix.count(A, C));
+        assert_eq!(2, matrix.count(C, C));
+    }
+}
@@ -0,0 +1,283 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conflict-free replicated sets.
+//!
+//! Every replica modifies its own copy of a set, and copies are combined
+//! with `merge`. Merging is commutative, associative and idempotent, so
+//! replicas which have seen the same modifications hold the same set,
+//! whatever the order in which they merged.
+
+use std::borrow;
+use std::cmp;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+use {bit, CLike, EnumSet};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+/// A set in which elements can be inserted and then removed, but not
+/// inserted again after being removed.
+pub struct TwoPhaseSet<E> {
+    added: EnumSet<E>,
+    removed: EnumSet<E>,
+}
+
+impl<E: CLike> TwoPhaseSet<E> {
+    /// Returns an empty `TwoPhaseSet`.
+    pub fn new() -> Self {
+        TwoPhaseSet { added: EnumSet::new(), removed: EnumSet::new() }
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// Returns `true` if the value was not already present and had never
+    /// been removed.
+    pub fn insert(&mut self, value: E) -> bool {
+        let value = bit(&value);
+        let result = (self.added.bits | self.removed.bits) & value == 0;
+        self.added.bits |= value;
+        result
+    }
+
+    /// Removes a value from the set, which prevents it from being inserted
+    /// again.
+    ///
+    /// Returns `true` if the value was present.
+    pub fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        let value = value.borrow();
+        let result = self.contains(value);
+        if result {
+            self.removed.bits |= bit(value);
+        }
+        result
+    }
+
+    /// Returns `true` if the set contains `value`.
+    pub fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        self.value().contains(value)
+    }
+
+    /// Returns the elements which were inserted and not removed.
+    pub fn value(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.added.bits & !self.removed.bits)
+    }
+
+    /// Merges the modifications made to `other` into the set.
+    pub fn merge(&mut self, other: &Self) {
+        self.added.bits |= other.added.bits;
+        self.removed.bits |= other.removed.bits;
+    }
+}
+
+impl<E: CLike> Default for TwoPhaseSet<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for TwoPhaseSet<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("TwoPhaseSet")
+           .field("added", &self.added)
+           .field("removed", &self.removed)
+           .finish()
+    }
+}
+
+/// An identifier of an insertion: the replica which made it and the value of
+/// its counter at the time.
+type Tag = (u32, u64);
+
+/// Checks if the insertion identified by `tag` happened before `clock`.
+fn has_seen(clock: &BTreeMap<u32, u64>, &(replica, counter): &Tag) -> bool {
+    clock.get(&replica).is_some_and(|&seen| seen >= counter)
+}
+
+/// An observed-remove set, in which elements can be inserted and removed any
+/// number of times.
+///
+/// When an element is concurrently inserted by one replica and removed by
+/// another, the insertion wins. Each element holds at most one tag per
+/// replica which inserted it, and removed elements hold no tags.
+#[derive(Clone)]
+pub struct ORSet<E> {
+    replica: u32,
+    // The latest counter value seen from each replica.
+    clock: BTreeMap<u32, u64>,
+    // The live insertion tags of each variant, sorted by replica.
+    tags: Vec<Vec<Tag>>,
+    phantom: PhantomData<E>,
+}
+
+impl<E: CLike> ORSet<E> {
+    /// Returns an empty `ORSet` modified by the replica identified by
+    /// `replica`.
+    ///
+    /// Every replica of a set must have a distinct identifier.
+    pub fn new(replica: u32) -> Self {
+        ORSet { replica, clock: BTreeMap::new(), tags: vec![Vec::new(); 32], phantom: PhantomData }
+    }
+
+    fn index(value: &E) -> usize {
+        bit(value).trailing_zeros() as usize
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// Returns `true` if the value was not already present.
+    pub fn insert(&mut self, value: E) -> bool {
+        let counter = self.clock.entry(self.replica).or_insert(0);
+        *counter += 1;
+        let tag = (self.replica, *counter);
+
+        let tags = &mut self.tags[Self::index(&value)];
+        let result = tags.is_empty();
+        match tags.binary_search_by_key(&self.replica, |&(replica, _)| replica) {
+            Ok(position) => tags[position] = tag,
+            Err(position) => tags.insert(position, tag),
+        }
+        result
+    }
+
+    /// Removes a value from the set, along with every insertion of it which
+    /// the replica has seen.
+    ///
+    /// Returns `true` if the value was present.
+    pub fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        let tags = &mut self.tags[Self::index(value.borrow())];
+        let result = !tags.is_empty();
+        tags.clear();
+        result
+    }
+
+    /// Returns `true` if the set contains `value`.
+    pub fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        !self.tags[Self::index(value.borrow())].is_empty()
+    }
+
+    /// Returns the elements of the set.
+    pub fn value(&self) -> EnumSet<E> {
+        let bits = self.tags
+                       .iter()
+                       .enumerate()
+                       .filter(|&(_, tags)| !tags.is_empty())
+                       .fold(0, |bits, (index, _)| bits | 1 << index);
+        EnumSet::new_with_bits(bits)
+    }
+
+    /// Merges the modifications made to `other` into the set.
+    pub fn merge(&mut self, other: &Self) {
+        let clock = &self.clock;
+        for (tags, other_tags) in self.tags.iter_mut().zip(&other.tags) {
+            // A tag known to only one side is live if the other side has not
+            // seen it, and removed otherwise.
+            let mut merged: Vec<Tag> = tags.iter()
+                                           .filter(|tag| {
+                                               other_tags.contains(tag) || !has_seen(&other.clock, tag)
+                                           })
+                                           .cloned()
+                                           .collect();
+            for tag in other_tags {
+                if !tags.contains(tag) && !has_seen(clock, tag) {
+                    merged.push(*tag);
+                }
+            }
+            // Keep only the latest tag of each replica.
+            merged.sort_unstable();
+            merged.reverse();
+            merged.dedup_by_key(|&mut (replica, _)| replica);
+            merged.reverse();
+            *tags = merged;
+        }
+        for (&replica, &counter) in &other.clock {
+            let seen = self.clock.entry(replica).or_insert(0);
+            *seen = cmp::max(*seen, counter);
+        }
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for ORSet<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ORSet")
+           .field("replica", &self.replica)
+           .field("value", &self.value())
+           .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::set;
+    use tests::Foo::*;
+
+    use super::{ORSet, TwoPhaseSet};
+
+    #[test]
+    fn test_two_phase_set() {
+        let mut s1 = TwoPhaseSet::new();
+        assert!(s1.insert(A));
+        assert!(s1.insert(B));
+        let mut s2 = s1;
+        assert!(s2.remove(A));
+        assert!(!s2.insert(A));
+        assert!(!s2.remove(C));
+        assert!(s1.insert(C));
+
+        let mut merged = s1;
+        merged.merge(&s2);
+        s2.merge(&s1);
+        assert_eq!(merged, s2);
+        assert_eq!(set(&[B, C]), merged.value());
+        assert!(!merged.contains(A));
+    }
+
+    #[test]
+    fn test_or_set_add_wins() {
+        let mut s1 = ORSet::new(1);
+        assert!(s1.insert(A));
+        assert!(!s1.insert(A));
+        s1.insert(B);
+        let mut s2 = s1.clone();
+        s2.merge(&s1);
+
+        // A concurrent removal and insertion of `A`.
+        assert!(s1.remove(A));
+        s2.insert(A);
+        // A removal of `B` unknown to `s1`.
+        assert!(s2.remove(B));
+
+        let mut m1 = s1.clone();
+        m1.merge(&s2);
+        let mut m2 = s2.clone();
+        m2.merge(&s1);
+        assert_eq!(set(&[A]), m1.value());
+        assert_eq!(set(&[A]), m2.value());
+        m1.merge(&m1.clone());
+        assert_eq!(set(&[A]), m1.value());
+    }
+
+    #[test]
+    fn test_or_set_reinsert() {
+        let mut s1 = ORSet::new(1);
+        let mut s2 = ORSet::new(2);
+        s1.insert(C);
+        s2.merge(&s1);
+        s2.remove(C);
+        s1.merge(&s2);
+        assert!(!s1.contains(C));
+
+        s1.insert(C);
+        s2.merge(&s1);
+        assert!(s2.contains(C));
+        assert_eq!(set(&[C]), s2.value());
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Use of sets in D-Bus messages.
+//!
+//! A set has the D-Bus signature `u`, holding its bits. Fields of interface
+//! structs should be serialized with `#[serde(with = "enum_set::serde_bits")]`
+//! to match.
+
+use std::convert::TryFrom;
+
+use zvariant::{Error, OwnedValue, Signature, Type, Value};
+
+use {universe, CLike, EnumSet, Finite};
+
+impl<E: CLike> Type for EnumSet<E> {
+    const SIGNATURE: &'static Signature = &Signature::U32;
+}
+
+impl<'a, E: CLike> From<EnumSet<E>> for Value<'a> {
+    fn from(set: EnumSet<E>) -> Value<'a> {
+        Value::U32(set.bits)
+    }
+}
+
+impl<'a, E: Finite> TryFrom<Value<'a>> for EnumSet<E> {
+    type Error = Error;
+
+    fn try_from(value: Value<'a>) -> Result<Self, Error> {
+        let bits = u32::try_from(value)?;
+        if bits & !universe::<E>() == 0 {
+            Ok(EnumSet::new_with_bits(bits))
+        } else {
+            Err(Error::OutOfBounds)
+        }
+    }
+}
+
+impl<E: Finite> TryFrom<OwnedValue> for EnumSet<E> {
+    type Error = Error;
+
+    fn try_from(value: OwnedValue) -> Result<Self, Error> {
+        EnumSet::try_from(Value::from(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use zvariant::{Error, Signature, Type, Value};
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_signature() {
+        assert_eq!(&Signature::U32, <EnumSet<Foo> as Type>::SIGNATURE);
+    }
+
+    #[test]
+    fn test_value_conversions() {
+        assert_eq!(Value::U32(0b101), Value::from(set(&[A, C])));
+        assert_eq!(Ok(set(&[A, C])), EnumSet::try_from(Value::U32(0b101)));
+        assert_eq!(Err(Error::OutOfBounds), EnumSet::<Foo>::try_from(Value::U32(0b1000)));
+        assert_eq!(Err(Error::IncorrectType), EnumSet::<Foo>::try_from(Value::U8(1)));
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use {universe, CLike, EnumSet, Finite};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// A value of one of two enums, for sets over both of them.
+///
+/// The variants of `A` take the first `A::VARIANT_COUNT` bits of an
+/// `EnumSet<Either<A, B>>` and the variants of `B` follow them, so the set
+/// holds elements of both enums as long as they fit in 32 bits together.
+///
+/// ```
+/// use enum_set::{CLike, Either, EnumSet, Finite};
+/// use std::mem;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u32)]
+/// enum Audio {
+///     Muted, Looping
+/// }
+///
+/// impl CLike for Audio {
+///     fn to_u32(&self) -> u32 {
+///         *self as u32
+///     }
+///
+///     unsafe fn from_u32(v: u32) -> Audio {
+///         mem::transmute(v)
+///     }
+/// }
+///
+/// unsafe impl Finite for Audio {
+///     const VARIANT_COUNT: u32 = 2;
+/// }
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u32)]
+/// enum Video {
+///     Paused, Fullscreen
+/// }
+///
+/// impl CLike for Video {
+///     fn to_u32(&self) -> u32 {
+///         *self as u32
+///     }
+///
+///     unsafe fn from_u32(v: u32) -> Video {
+///         mem::transmute(v)
+///     }
+/// }
+///
+/// let mut flags = EnumSet::new();
+/// flags.insert(Either::Left(Audio::Muted));
+/// flags.insert(Either::Right(Video::Fullscreen));
+///
+/// assert_eq!(vec![Audio::Muted], flags.left().iter().collect::<Vec<_>>());
+/// assert_eq!(vec![Video::Fullscreen], flags.right().iter().collect::<Vec<_>>());
+/// ```
+pub enum Either<A, B> {
+    /// A value of the first enum.
+    Left(A),
+    /// A value of the second enum.
+    Right(B),
+}
+
+impl<A: Finite, B: CLike> CLike for Either<A, B> {
+    fn to_u32(&self) -> u32 {
+        match *self {
+            Either::Left(ref value) => value.to_u32(),
+            Either::Right(ref value) => A::VARIANT_COUNT + value.to_u32(),
+        }
+    }
+
+    unsafe fn from_u32(value: u32) -> Self {
+        if value < A::VARIANT_COUNT {
+            Either::Left(A::from_u32(value))
+        } else {
+            Either::Right(B::from_u32(value - A::VARIANT_COUNT))
+        }
+    }
+}
+
+unsafe impl<A: Finite, B: Finite> Finite for Either<A, B> {
+    const VARIANT_COUNT: u32 = {
+        assert!(A::VARIANT_COUNT + B::VARIANT_COUNT <= 32,
+                "Either only supports up to 32 variants in total.");
+        A::VARIANT_COUNT + B::VARIANT_COUNT
+    };
+}
+
+impl<A: Finite, B: CLike> EnumSet<Either<A, B>> {
+    /// Returns the set containing the elements of `left` and `right`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an element of `right` does not fit in the bits following
+    /// the variants of `A`.
+    pub fn from_parts(left: EnumSet<A>, right: EnumSet<B>) -> Self {
+        let shifted = right.bits.checked_shl(A::VARIANT_COUNT).unwrap_or(0);
+        assert!(shifted.checked_shr(A::VARIANT_COUNT).unwrap_or(0) == right.bits,
+                "EnumSet only supports up to {} variants.", 31);
+        EnumSet::new_with_bits(left.bits | shifted)
+    }
+
+    /// Returns the elements of the set which are values of `A`.
+    pub fn left(&self) -> EnumSet<A> {
+        EnumSet::new_with_bits(self.bits & universe::<A>())
+    }
+
+    /// Returns the elements of the set which are values of `B`.
+    pub fn right(&self) -> EnumSet<B> {
+        EnumSet::new_with_bits(self.bits.checked_shr(A::VARIANT_COUNT).unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use {EnumSet, Finite};
+    use super::Either::{self, Left, Right};
+
+    #[test]
+    fn test_parts() {
+        let mut e = EnumSet::new();
+        e.insert(Left(B));
+        e.insert(Right(A));
+        e.insert(Right(C));
+        assert_eq!(0b101010, e.bits);
+        assert_eq!(set(&[B]), e.left());
+        assert_eq!(set(&[A, C]), e.right());
+        assert_eq!(e, EnumSet::from_parts(set(&[B]), set(&[A, C])));
+        assert_eq!(vec![Left(B), Right(A), Right(C)], e.iter().collect::<Vec<Either<Foo, Foo>>>());
+    }
+
+    #[test]
+    fn test_finite() {
+        assert_eq!(6, Either::<Foo, Foo>::VARIANT_COUNT);
+        assert_eq!(6, EnumSet::<Either<Foo, Foo>>::all().len());
+    }
+}
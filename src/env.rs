@@ -0,0 +1,165 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parsing of sets from environment variables.
+
+use std::env;
+use std::error;
+use std::fmt;
+
+use {parse, EnumSet, Finite};
+
+/// An error returned by `EnumSet::from_env`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EnvError {
+    /// The variable is not set.
+    NotPresent {
+        /// The name of the variable.
+        var: String,
+    },
+    /// The variable is not valid Unicode.
+    NotUnicode {
+        /// The name of the variable.
+        var: String,
+    },
+    /// The variable names something which is not a variant.
+    UnknownName {
+        /// The name of the variable.
+        var: String,
+        /// The unknown name.
+        name: String,
+        /// The name of the variant closest to the unknown name, if it is
+        /// close enough to be a likely typo.
+        suggestion: Option<String>,
+    },
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EnvError::NotPresent { ref var } => {
+                write!(fmt, "environment variable `{}` is not set", var)
+            }
+            EnvError::NotUnicode { ref var } => {
+                write!(fmt, "environment variable `{}` is not valid unicode", var)
+            }
+            EnvError::UnknownName { ref var, ref name, ref suggestion } => {
+                write!(fmt, "environment variable `{}` contains unknown variant `{}`", var, name)?;
+                if let Some(ref suggestion) = *suggestion {
+                    write!(fmt, ", did you mean `{}`?", suggestion)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl error::Error for EnvError {}
+
+fn parse<E: Finite + fmt::Debug>(var: &str, value: &str) -> Result<EnumSet<E>, EnvError> {
+    parse::parse_names(value).map_err(|err| EnvError::UnknownName {
+        var: var.to_owned(),
+        name: err.token().to_owned(),
+        suggestion: err.suggestion().map(str::to_owned),
+    })
+}
+
+impl<E: Finite + fmt::Debug> EnumSet<E> {
+    /// Reads a set from the environment variable `var`, which holds variant
+    /// names separated by commas or whitespace.
+    ///
+    /// Names are the `Debug` representations of the variants in
+    /// `Finite::NAME_CASE`.
+    ///
+    /// ```
+    /// use enum_set::{CLike, EnumSet, Finite};
+    /// use std::{env, mem};
+    ///
+    /// #[derive(Clone, Copy, Debug, PartialEq)]
+    /// #[repr(u32)]
+    /// enum Feature {
+    ///     Metrics, Tracing, Cache
+    /// }
+    ///
+    /// impl CLike for Feature {
+    ///     fn to_u32(&self) -> u32 {
+    ///         *self as u32
+    ///     }
+    ///
+    ///     unsafe fn from_u32(v: u32) -> Feature {
+    ///         mem::transmute(v)
+    ///     }
+    /// }
+    ///
+    /// unsafe impl Finite for Feature {
+    ///     const VARIANT_COUNT: u32 = 3;
+    /// }
+    ///
+    /// env::set_var("FEATURES", "Metrics, Cache");
+    /// let features: EnumSet<Feature> = EnumSet::from_env("FEATURES").unwrap();
+    /// assert_eq!(vec![Feature::Metrics, Feature::Cache], features.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn from_env(var: &str) -> Result<Self, EnvError> {
+        match env::var(var) {
+            Ok(value) => parse(var, &value),
+            Err(env::VarError::NotPresent) => Err(EnvError::NotPresent { var: var.to_owned() }),
+            Err(env::VarError::NotUnicode(_)) => Err(EnvError::NotUnicode { var: var.to_owned() }),
+        }
+    }
+
+    /// Like `from_env`, but returns `default` if `var` is not set.
+    pub fn from_env_or_default(var: &str, default: Self) -> Result<Self, EnvError> {
+        match Self::from_env(var) {
+            Err(EnvError::NotPresent { .. }) => Ok(default),
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::EnvError;
+
+    #[test]
+    fn test_from_env() {
+        env::set_var("ENUM_SET_TEST_FROM_ENV", " A,C  B,,");
+        assert_eq!(Ok(set(&[A, B, C])), EnumSet::from_env("ENUM_SET_TEST_FROM_ENV"));
+
+        env::set_var("ENUM_SET_TEST_FROM_ENV_EMPTY", "");
+        assert_eq!(Ok(set(&[])), EnumSet::from_env("ENUM_SET_TEST_FROM_ENV_EMPTY"));
+
+        env::set_var("ENUM_SET_TEST_FROM_ENV_UNKNOWN", "A,D");
+        assert_eq!(Err(EnvError::UnknownName {
+                       var: "ENUM_SET_TEST_FROM_ENV_UNKNOWN".to_owned(),
+                       name: "D".to_owned(),
+                       suggestion: Some("A".to_owned()),
+                   }),
+                   EnumSet::<Foo>::from_env("ENUM_SET_TEST_FROM_ENV_UNKNOWN"));
+    }
+
+    #[test]
+    fn test_from_env_or_default() {
+        env::remove_var("ENUM_SET_TEST_MISSING");
+        assert_eq!(Err(EnvError::NotPresent { var: "ENUM_SET_TEST_MISSING".to_owned() }),
+                   EnumSet::<Foo>::from_env("ENUM_SET_TEST_MISSING"));
+        assert_eq!(Ok(set(&[B])),
+                   EnumSet::from_env_or_default("ENUM_SET_TEST_MISSING", set(&[B])));
+
+        env::set_var("ENUM_SET_TEST_PRESENT", "A");
+        assert_eq!(Ok(set(&[A])),
+                   EnumSet::from_env_or_default("ENUM_SET_TEST_PRESENT", set(&[B])));
+    }
+}
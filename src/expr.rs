@@ -0,0 +1,288 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Evaluation of boolean expressions over variant names.
+
+use std::error;
+use std::fmt;
+
+use {bit, names, EnumSet, Finite};
+
+/// The maximum number of nested `!` and parentheses in an expression.
+const MAX_DEPTH: usize = 64;
+
+/// An error returned by `EnumSet::eval_expr`.
+///
+/// Offsets are byte offsets into the expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExprError {
+    /// A name does not correspond to a variant.
+    UnknownName {
+        /// The offset of the name.
+        offset: usize,
+        /// The unknown name.
+        name: String,
+        /// The name of the variant closest to the unknown name, if it is
+        /// close enough to be a likely typo.
+        suggestion: Option<String>,
+    },
+    /// A character cannot appear at this position.
+    UnexpectedCharacter {
+        /// The offset of the character.
+        offset: usize,
+    },
+    /// The expression ended where an operand or `)` was expected.
+    UnexpectedEnd,
+    /// The expression nests `!` or parentheses too deeply.
+    TooDeep {
+        /// The offset of the `!` or `(` exceeding the limit.
+        offset: usize,
+    },
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ExprError::UnknownName { offset, ref name, ref suggestion } => {
+                write!(fmt, "unknown variant `{}` at offset {}", name, offset)?;
+                if let Some(ref suggestion) = *suggestion {
+                    write!(fmt, ", did you mean `{}`?", suggestion)?;
+                }
+                Ok(())
+            }
+            ExprError::UnexpectedCharacter { offset } => {
+                write!(fmt, "unexpected character at offset {}", offset)
+            }
+            ExprError::UnexpectedEnd => fmt.write_str("unexpected end of expression"),
+            ExprError::TooDeep { offset } => {
+                write!(fmt, "expression nested too deeply at offset {}", offset)
+            }
+        }
+    }
+}
+
+impl error::Error for ExprError {}
+
+struct Parser<'a> {
+    input: &'a str,
+    offset: usize,
+    context: u32,
+    depth: usize,
+}
+
+fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-'
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&mut self) -> Option<u8> {
+        let skipped = self.input[self.offset..].len() - self.input[self.offset..].trim_start().len();
+        self.offset += skipped;
+        self.input.as_bytes().get(self.offset).cloned()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.offset += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or<E: Finite + fmt::Debug>(&mut self) -> Result<bool, ExprError> {
+        let mut value = self.xor::<E>()?;
+        while self.eat(b'|') {
+            value |= self.xor::<E>()?;
+        }
+        Ok(value)
+    }
+
+    fn xor<E: Finite + fmt::Debug>(&mut self) -> Result<bool, ExprError> {
+        let mut value = self.and::<E>()?;
+        while self.eat(b'^') {
+            value ^= self.and::<E>()?;
+        }
+        Ok(value)
+    }
+
+    fn and<E: Finite + fmt::Debug>(&mut self) -> Result<bool, ExprError> {
+        let mut value = self.unary::<E>()?;
+        while self.eat(b'&') {
+            value &= self.unary::<E>()?;
+        }
+        Ok(value)
+    }
+
+    fn unary<E: Finite + fmt::Debug>(&mut self) -> Result<bool, ExprError> {
+        match self.peek() {
+            Some(b'!') => {
+                self.enter()?;
+                let value = !self.unary::<E>()?;
+                self.depth -= 1;
+                Ok(value)
+            }
+            Some(b'(') => {
+                self.enter()?;
+                let value = self.or::<E>()?;
+                self.depth -= 1;
+                if self.eat(b')') {
+                    Ok(value)
+                } else {
+                    Err(self.unexpected())
+                }
+            }
+            Some(byte) if is_name_byte(byte) => {
+                let start = self.offset;
+                while self.input.as_bytes().get(self.offset).is_some_and(|&b| is_name_byte(b)) {
+                    self.offset += 1;
+                }
+                let name = &self.input[start..self.offset];
+                match names::from_name::<E>(name) {
+                    Some(variant) => Ok(self.context & bit(&variant) != 0),
+                    None => Err(ExprError::UnknownName {
+                        offset: start,
+                        name: name.to_owned(),
+                        suggestion: names::suggest::<E>(name),
+                    }),
+                }
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    // Consumes a `!` or `(`, which starts a nested operand.
+    fn enter(&mut self) -> Result<(), ExprError> {
+        if self.depth == MAX_DEPTH {
+            return Err(ExprError::TooDeep { offset: self.offset });
+        }
+        self.depth += 1;
+        self.offset += 1;
+        Ok(())
+    }
+
+    fn unexpected(&mut self) -> ExprError {
+        match self.peek() {
+            Some(_) => ExprError::UnexpectedCharacter { offset: self.offset },
+            None => ExprError::UnexpectedEnd,
+        }
+    }
+}
+
+impl<E: Finite + fmt::Debug> EnumSet<E> {
+    /// Evaluates a boolean expression over variant names, where every name is
+    /// `true` if `context` contains the variant.
+    ///
+    /// Names are the `Debug` representations of the variants in
+    /// `Finite::NAME_CASE`, and can contain `-`. The operators are `!` (not),
+    /// `&` (and), `^` (exclusive or) and `|` (or), from highest to lowest
+    /// precedence, and parentheses can be used for grouping. At most 64 `!`
+    /// and parentheses can be nested.
+    ///
+    /// ```
+    /// use enum_set::{CLike, EnumSet, Finite};
+    /// use std::mem;
+    ///
+    /// #[derive(Clone, Copy, Debug)]
+    /// #[repr(u32)]
+    /// enum Alert {
+    ///     Disk, Memory, Network
+    /// }
+    ///
+    /// impl CLike for Alert {
+    ///     fn to_u32(&self) -> u32 {
+    ///         *self as u32
+    ///     }
+    ///
+    ///     unsafe fn from_u32(v: u32) -> Alert {
+    ///         mem::transmute(v)
+    ///     }
+    /// }
+    ///
+    /// unsafe impl Finite for Alert {
+    ///     const VARIANT_COUNT: u32 = 3;
+    /// }
+    ///
+    /// let mut active = EnumSet::new();
+    /// active.insert(Alert::Disk);
+    /// assert_eq!(Ok(true), EnumSet::eval_expr("Disk & !(Memory | Network)", &active));
+    /// assert_eq!(Ok(false), EnumSet::eval_expr("Disk & Memory", &active));
+    /// ```
+    pub fn eval_expr(expr: &str, context: &Self) -> Result<bool, ExprError> {
+        let mut parser = Parser { input: expr, offset: 0, context: context.bits, depth: 0 };
+        let value = parser.or::<E>()?;
+        match parser.peek() {
+            None => Ok(value),
+            Some(_) => Err(parser.unexpected()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::{set, Mode};
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::ExprError;
+
+    #[test]
+    fn test_eval() {
+        let context = set(&[A, C]);
+        assert_eq!(Ok(true), EnumSet::eval_expr("A", &context));
+        assert_eq!(Ok(false), EnumSet::eval_expr("B", &context));
+        assert_eq!(Ok(true), EnumSet::eval_expr("A & !B", &context));
+        assert_eq!(Ok(false), EnumSet::eval_expr("A & !(B | C)", &context));
+        assert_eq!(Ok(true), EnumSet::eval_expr("B & A | C", &context));
+        assert_eq!(Ok(false), EnumSet::eval_expr("A ^ C", &context));
+        assert_eq!(Ok(true), EnumSet::eval_expr("!!( A )", &context));
+    }
+
+    #[test]
+    fn test_name_case() {
+        let context: EnumSet<Mode> = [Mode::ReadOnly].iter().collect();
+        assert_eq!(Ok(true), EnumSet::eval_expr("read-only & !HTTP-Proxy", &context));
+        assert_eq!(Ok(false), EnumSet::eval_expr("read-write|http-proxy", &context));
+    }
+
+    #[test]
+    fn test_errors() {
+        let context = set(&[]);
+        assert_eq!(Err(ExprError::UnknownName {
+                       offset: 4,
+                       name: "D".to_owned(),
+                       suggestion: Some("A".to_owned()),
+                   }),
+                   EnumSet::eval_expr("A | D", &context));
+        assert_eq!(Err(ExprError::UnknownName { offset: 0, name: "Both".to_owned(), suggestion: None }),
+                   EnumSet::eval_expr("Both", &context));
+        assert_eq!(Err(ExprError::UnexpectedCharacter { offset: 2 }),
+                   EnumSet::eval_expr("A B", &context));
+        assert_eq!(Err(ExprError::UnexpectedCharacter { offset: 4 }),
+                   EnumSet::eval_expr("A & &B", &context));
+        assert_eq!(Err(ExprError::UnexpectedEnd), EnumSet::eval_expr("(A | B", &context));
+        assert_eq!(Err(ExprError::UnexpectedEnd), EnumSet::eval_expr("", &context));
+    }
+
+    #[test]
+    fn test_depth() {
+        let context = set(&[A]);
+        let nested = format!("{}A{}", "(".repeat(64), ")".repeat(64));
+        assert_eq!(Ok(true), EnumSet::eval_expr(&nested, &context));
+        let negated = format!("{}A", "!".repeat(64));
+        assert_eq!(Ok(true), EnumSet::eval_expr(&negated, &context));
+        let mixed = format!("(A) & {}!A", "!(".repeat(32));
+        assert_eq!(Err(ExprError::TooDeep { offset: 70 }), EnumSet::eval_expr(&mixed, &context));
+        let deep = "!".repeat(1_000_000);
+        assert_eq!(Err(ExprError::TooDeep { offset: 64 }), EnumSet::eval_expr(&deep, &context));
+        let deep = "(".repeat(1_000_000);
+        assert_eq!(Err(ExprError::TooDeep { offset: 64 }), EnumSet::eval_expr(&deep, &context));
+    }
+}
@@ -0,0 +1,430 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::str;
+
+/// Defines a C-like enum along with named sets of its variants.
+///
+/// The enum gets implementations of `CLike`, `Finite` and `Describe`, where
+/// the description of a variant is its doc comment with one leading space
+/// removed from every line, as rustdoc does. It also derives `Clone` and
+/// `Copy`, so these must not be derived again.
+///
+/// The bit position of a variant is its discriminant. It follows declaration
+/// order by default, and can be pinned with an explicit discriminant, such
+/// as `Exec = 2`, so that reordering the declarations does not change the
+/// positions of sets which were already stored. The positions must be the
+/// numbers from zero to the number of variants, in any order, which is
+/// checked at compile time.
+///
+/// Gating a variant with `#[cfg(...)]` is a compile error. Removing a
+/// variant in some builds would shift the default positions of the variants
+/// after it, making sets encoded by differently configured builds
+/// incompatible, and would leave a gap below `VARIANT_COUNT`, which `Finite`
+/// does not allow. Keep such variants in every build and gate their use
+/// instead.
+///
+/// A variant can list former names with `alias` options, such as
+/// `Exec(alias = "Execute", alias = "Run")`. They are stored in
+/// `Finite::ALIASES`, so that parsing by name, with `FromStr`, `parse_names`
+/// and the serde modules using names, still accepts them after a rename.
+///
+/// Each named set becomes an associated constant of the enum holding an
+/// `EnumSet` of the listed variants. An optional `default` clause lists the
+/// variants enabled by default, and implements `DefaultSet` so that
+/// `EnumSet::default_set` returns them.
+///
+/// It can be followed by a `rename_all` clause, such as
+/// `rename_all = KebabCase;`, which names the variants in one of the cases of
+/// `NameCase` and implements `Display` with these names, and by a
+/// `case_insensitive = true;` clause, which makes lookups by name ignore
+/// case. They set `Finite::NAME_CASE` and `Finite::CASE_INSENSITIVE_NAMES`,
+/// which `FromStr`, `parse_names` and the serde modules using names follow.
+///
+/// ```
+/// #[macro_use]
+/// extern crate enum_set;
+///
+/// use enum_set::EnumSet;
+///
+/// enum_set_flags! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Perm {
+///         Read,
+///         Write,
+///         Exec(alias = "Execute"),
+///     }
+///
+///     default = Read;
+///
+///     impl Perm {
+///         pub const READ_WRITE = Read | Write;
+///         pub const ALL = Read | Write | Exec;
+///     }
+/// }
+///
+/// fn main() {
+///     assert_eq!(vec![Perm::Read, Perm::Write], Perm::READ_WRITE.iter().collect::<Vec<_>>());
+///     assert!(Perm::ALL.contains(&Perm::Exec));
+///     assert_eq!(vec![Perm::Read], EnumSet::<Perm>::default_set().iter().collect::<Vec<_>>());
+///     assert_eq!(Perm::ALL, "Read, Write, Execute".parse().unwrap());
+/// }
+/// ```
+///
+/// ```
+/// #[macro_use]
+/// extern crate enum_set;
+///
+/// use enum_set::EnumSet;
+///
+/// enum_set_flags! {
+///     #[derive(Debug)]
+///     pub enum Feature {
+///         DarkMode,
+///         AutoSave,
+///     }
+///
+///     rename_all = KebabCase;
+///
+///     case_insensitive = true;
+/// }
+///
+/// fn main() {
+///     let features: EnumSet<Feature> = "Dark-Mode, auto-save".parse().unwrap();
+///     assert_eq!("dark-mode, auto-save", features.to_string());
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate enum_set;
+///
+/// enum_set_flags! {
+///     pub enum Perm {
+///         Read,
+///         #[cfg(unix)]
+///         Write,
+///     }
+/// }
+///
+/// fn main() {}
+/// ```
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate enum_set;
+///
+/// enum_set_flags! {
+///     pub enum Perm {
+///         Read,
+///         Write = 40,
+///     }
+/// }
+///
+/// fn main() {}
+/// ```
+#[macro_export]
+macro_rules! enum_set_flags {
+    (
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$($variant_attr:tt)*])*
+                $variant:ident $(($(alias = $alias:literal),+ $(,)*))? $(= $position:expr)?
+            ),+ $(,)*
+        }
+
+        $(default = $($default:ident)|+;)?
+
+        $(rename_all = $case:ident;)?
+
+        $(case_insensitive = $case_insensitive:literal;)?
+
+        $(
+            impl $impl_name:ident {
+                $(
+                    $(#[$const_attr:meta])*
+                    $const_vis:vis const $const_name:ident = $($member:ident)|+;
+                )*
+            }
+        )*
+    ) => {
+        $($($crate::__enum_set_flags_check_attr!($($variant_attr)*);)*)+
+
+        $(#[$attr])*
+        #[derive(Clone, Copy)]
+        #[repr(u32)]
+        $vis enum $name {
+            $($(#[$($variant_attr)*])* $variant $(= $position)?),+
+        }
+
+        const _: () = {
+            let count = <$name as $crate::Finite>::VARIANT_COUNT;
+            $(
+                assert!(($name::$variant as u32) < 32,
+                        concat!("the position of `", stringify!($variant), "` is not below 32"));
+                assert!(($name::$variant as u32) < count,
+                        concat!("the position of `", stringify!($variant),
+                                "` is not below the number of variants"));
+            )+
+        };
+
+        impl $crate::CLike for $name {
+            fn to_u32(&self) -> u32 {
+                *self as u32
+            }
+
+            unsafe fn from_u32(v: u32) -> $name {
+                ::std::mem::transmute(v)
+            }
+        }
+
+        unsafe impl $crate::Finite for $name {
+            const VARIANT_COUNT: u32 = [$(stringify!($variant)),+].len() as u32;
+            const ALIASES: &'static [(&'static str, u32)] =
+                &[$($($(($alias, $name::$variant as u32),)+)?)+];
+            $(const NAME_CASE: $crate::NameCase = $crate::NameCase::$case;)?
+            $(const CASE_INSENSITIVE_NAMES: bool = $case_insensitive;)?
+        }
+
+        $crate::__enum_set_flags_display!($name [$($case)?] $($variant)+);
+
+        impl $crate::Describe for $name {
+            fn description(&self) -> &'static str {
+                match *self {
+                    $($name::$variant => {
+                        const DOC: &str = $crate::__enum_set_flags_doc!($(#[$($variant_attr)*])*);
+                        static DESCRIPTION: $crate::__Description<{ DOC.len() }> =
+                            $crate::__Description::new(DOC);
+                        DESCRIPTION.as_str()
+                    })+
+                }
+            }
+        }
+
+        $(
+            impl $crate::DefaultSet for $name {
+                const DEFAULT_SET: $crate::EnumSet<$name> =
+                    $crate::EnumSet::__from_bits(0 $(| 1 << ($name::$default as u32))+);
+            }
+        )?
+
+        $(
+            impl $impl_name {
+                $(
+                    $(#[$const_attr])*
+                    $const_vis const $const_name: $crate::EnumSet<$name> =
+                        $crate::EnumSet::__from_bits(0 $(| 1 << ($name::$member as u32))+);
+                )*
+            }
+        )*
+    };
+}
+
+// Used by `enum_set_flags!` to turn the doc comments of a variant into its
+// description. As rustdoc does, one leading space is removed from every line,
+// and the whitespace around the text is removed too.
+#[doc(hidden)]
+pub struct __Description<const N: usize> {
+    bytes: [u8; N],
+    start: usize,
+    end: usize,
+}
+
+impl<const N: usize> __Description<N> {
+    pub const fn new(doc: &str) -> Self {
+        let doc = doc.as_bytes();
+        let mut bytes = [0; N];
+        let mut end = 0;
+        let mut i = 0;
+        while i < doc.len() {
+            if doc[i] != b' ' || (i > 0 && doc[i - 1] != b'\n') {
+                bytes[end] = doc[i];
+                end += 1;
+            }
+            i += 1;
+        }
+        let mut start = 0;
+        while start < end && bytes[start].is_ascii_whitespace() {
+            start += 1;
+        }
+        while end > start && bytes[end - 1].is_ascii_whitespace() {
+            end -= 1;
+        }
+        __Description { bytes, start, end }
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Safe because only ASCII characters were removed from a string.
+        unsafe { str::from_utf8_unchecked(&self.bytes[self.start..self.end]) }
+    }
+}
+
+// Rejects `#[cfg]` on a variant, including inside `#[cfg_attr]`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __enum_set_flags_check_attr {
+    (cfg $($rest:tt)*) => {
+        compile_error!("variants of `enum_set_flags!` cannot be gated with `#[cfg]`");
+    };
+    (cfg_attr ( $($args:tt)* )) => {
+        $crate::__enum_set_flags_check_attr!(@cfg_attr $($args)*);
+    };
+    (@cfg_attr) => {};
+    (@cfg_attr cfg $($rest:tt)*) => {
+        $crate::__enum_set_flags_check_attr!(cfg);
+    };
+    (@cfg_attr cfg_attr ( $($args:tt)* ) $($rest:tt)*) => {
+        $crate::__enum_set_flags_check_attr!(@cfg_attr $($args)* , $($rest)*);
+    };
+    (@cfg_attr $first:tt $($rest:tt)*) => {
+        $crate::__enum_set_flags_check_attr!(@cfg_attr $($rest)*);
+    };
+    ($($attr:tt)*) => {};
+}
+
+// Concatenates the doc comments among the given attributes.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __enum_set_flags_doc {
+    () => { "" };
+    (#[doc = $doc:literal] $(#[$($rest:tt)*])*) => {
+        concat!($doc, "\n", $crate::__enum_set_flags_doc!($(#[$($rest)*])*))
+    };
+    (#[$($attr:tt)*] $(#[$($rest:tt)*])*) => {
+        $crate::__enum_set_flags_doc!($(#[$($rest)*])*)
+    };
+}
+
+// Implements `Display` with the names of the variants if they have a case.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __enum_set_flags_display {
+    ($name:ident [] $($variant:ident)+) => {};
+    ($name:ident [$case:ident] $($variant:ident)+) => {
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let name = match *self {
+                    $($name::$variant => stringify!($variant)),+
+                };
+                fmt.write_str(&$crate::NameCase::$case.apply(name))
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::Mode;
+
+    use {CLike, Describe, EnumSet, Finite, NameCase};
+
+    enum_set_flags! {
+        #[derive(Debug, PartialEq)]
+        enum Color {
+            /// Red.
+            Red,
+            Green,
+            /// Blue, the color
+            /// of the sky.
+            #[allow(dead_code)]
+            Blue,
+        }
+
+        default = Red | Blue;
+
+        impl Color {
+            const WARM = Red;
+            /// Every color but red.
+            const COOL = Green | Blue;
+        }
+    }
+
+    enum_set_flags! {
+        #[derive(Debug, PartialEq)]
+        enum Level {
+            Low(alias = "Minor") = 2,
+            High = 0,
+            Medium(alias = "Mid", alias = "Average",),
+        }
+
+        impl Level {
+            const EXTREMES = Low | High;
+        }
+    }
+
+    enum_set_flags! {
+        #[derive(Debug, PartialEq)]
+        enum Signal {
+            SigInt,
+            SigTerm,
+        }
+
+        rename_all = ScreamingSnakeCase;
+    }
+
+    #[test]
+    fn test_flags() {
+        assert_eq!(3, Color::VARIANT_COUNT);
+        assert_eq!(vec![Color::Red], Color::WARM.iter().collect::<Vec<_>>());
+        assert_eq!(vec![Color::Green, Color::Blue], Color::COOL.iter().collect::<Vec<_>>());
+        assert_eq!(EnumSet::all(), Color::WARM | Color::COOL);
+    }
+
+    #[test]
+    fn test_default_set() {
+        let set: EnumSet<Color> = EnumSet::default_set();
+        assert_eq!(vec![Color::Red, Color::Blue], set.iter().collect::<Vec<_>>());
+        assert!(EnumSet::<Color>::default().is_empty());
+    }
+
+    #[test]
+    fn test_positions() {
+        assert_eq!(3, Level::VARIANT_COUNT);
+        assert_eq!(2, Level::Low.to_u32());
+        assert_eq!(1, Level::Medium.to_u32());
+        let all: EnumSet<Level> = EnumSet::all();
+        assert_eq!(vec![Level::High, Level::Medium, Level::Low], all.iter().collect::<Vec<_>>());
+        assert_eq!(vec![Level::High, Level::Low], Level::EXTREMES.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_aliases() {
+        assert_eq!(&[("Minor", 2), ("Mid", 1), ("Average", 1)], Level::ALIASES);
+        assert!(Color::ALIASES.is_empty());
+        let set: EnumSet<Level> = "Minor, Average".parse().unwrap();
+        assert_eq!(vec![Level::Medium, Level::Low], set.iter().collect::<Vec<_>>());
+        assert_eq!(set, "Mid Low".parse().unwrap());
+    }
+
+    #[test]
+    fn test_name_case() {
+        assert_eq!(NameCase::ScreamingSnakeCase, Signal::NAME_CASE);
+        assert_eq!(NameCase::Unchanged, Color::NAME_CASE);
+        assert_eq!("SIG_TERM", Signal::SigTerm.to_string());
+        let all: EnumSet<Signal> = EnumSet::all();
+        assert_eq!("SIG_INT, SIG_TERM", all.to_string());
+        assert_eq!(all, "SIG_TERM SIG_INT".parse().unwrap());
+        assert!("SigInt".parse::<EnumSet<Signal>>().is_err());
+        assert!("sig_int".parse::<EnumSet<Signal>>().is_err());
+
+        assert_eq!(NameCase::KebabCase, Mode::NAME_CASE);
+        let modes: EnumSet<Mode> = [Mode::ReadOnly, Mode::HTTPProxy].iter().collect();
+        assert_eq!("read-only, http-proxy", modes.to_string());
+        assert_eq!(modes, "Read-Only, HTTP-PROXY".parse().unwrap());
+    }
+
+    #[test]
+    fn test_descriptions() {
+        assert_eq!("Red.", Color::Red.description());
+        assert_eq!("", Color::Green.description());
+        assert_eq!("Blue, the color\nof the sky.", Color::Blue.description());
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use metrics::{gauge, Gauge};
+
+use {names, EnumSet, Finite};
+
+/// Gauges reporting which variants a set contains, one per variant.
+///
+/// The gauges share the metric name given to `register` and are told apart
+/// by a `variant` label holding the `Debug` representation of the variant
+/// in `Finite::NAME_CASE`.
+/// `update` sets the gauge of each variant to 1 if the set contains it and
+/// to 0 otherwise.
+///
+/// ```ignore
+/// let gauges = SetGauges::<Feature>::register("service_features_enabled");
+/// gauges.update(&enabled_features);
+/// ```
+pub struct SetGauges<E> {
+    gauges: Vec<Gauge>,
+    phantom: PhantomData<E>,
+}
+
+impl<E: Finite + fmt::Debug> SetGauges<E> {
+    /// Registers the gauges of every variant of `E` under `name` with the
+    /// current recorder.
+    pub fn register(name: &str) -> Self {
+        let gauges = names::variants::<E>().map(|(_, variant)| {
+            gauge!(name.to_owned(), "variant" => variant)
+        }).collect();
+        SetGauges { gauges, phantom: PhantomData }
+    }
+}
+
+impl<E: Finite> SetGauges<E> {
+    /// Sets the gauges to the membership of the variants in `set`.
+    pub fn update(&self, set: &EnumSet<E>) {
+        for (index, gauge) in self.gauges.iter().enumerate() {
+            gauge.set(if set.bits & (1 << index) != 0 { 1.0 } else { 0.0 });
+        }
+    }
+}
+
+impl<E> fmt::Debug for SetGauges<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("SetGauges").field("len", &self.gauges.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use metrics::{self, Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder,
+                  SharedString, Unit};
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use super::SetGauges;
+
+    #[derive(Default)]
+    struct TestRecorder {
+        gauges: Mutex<Vec<(String, String, Arc<AtomicU64>)>>,
+    }
+
+    impl TestRecorder {
+        fn values(&self) -> Vec<(String, String, f64)> {
+            self.gauges.lock().unwrap().iter().map(|(name, variant, value)| {
+                (name.clone(), variant.clone(), f64::from_bits(value.load(Ordering::SeqCst)))
+            }).collect()
+        }
+    }
+
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, _: &Key, _: &Metadata) -> Counter {
+            Counter::noop()
+        }
+
+        fn register_gauge(&self, key: &Key, _: &Metadata) -> Gauge {
+            let value = Arc::new(AtomicU64::new(0));
+            let variant = key.labels().find(|label| label.key() == "variant").unwrap();
+            self.gauges.lock().unwrap().push((key.name().to_owned(),
+                                              variant.value().to_owned(),
+                                              value.clone()));
+            Gauge::from_arc(value)
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    fn expected(a: f64, b: f64, c: f64) -> Vec<(String, String, f64)> {
+        vec![("flags".to_owned(), "A".to_owned(), a),
+             ("flags".to_owned(), "B".to_owned(), b),
+             ("flags".to_owned(), "C".to_owned(), c)]
+    }
+
+    #[test]
+    fn test_update() {
+        let recorder = TestRecorder::default();
+        let gauges = metrics::with_local_recorder(&recorder, || SetGauges::<Foo>::register("flags"));
+        assert_eq!(expected(0.0, 0.0, 0.0), recorder.values());
+        gauges.update(&set(&[A, C]));
+        assert_eq!(expected(1.0, 0.0, 1.0), recorder.values());
+        gauges.update(&set(&[B]));
+        assert_eq!(expected(0.0, 1.0, 0.0), recorder.values());
+    }
+}
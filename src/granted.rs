@@ -0,0 +1,131 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::borrow;
+use std::fmt;
+
+use {CLike, EnumSet};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+/// A set which is known to be a subset of an authority set.
+///
+/// A `Granted` can only be created by checking it against an authority, and
+/// can only be shrunk afterwards, so functions taking a `Granted` cannot be
+/// handed more than the authority allowed.
+///
+/// ```
+/// use enum_set::{CLike, EnumSet, Granted};
+/// use std::mem;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u32)]
+/// enum Permission {
+///     Read, Write, Admin
+/// }
+///
+/// impl CLike for Permission {
+///     fn to_u32(&self) -> u32 {
+///         *self as u32
+///     }
+///
+///     unsafe fn from_u32(v: u32) -> Permission {
+///         mem::transmute(v)
+///     }
+/// }
+///
+/// let mut authority = EnumSet::new();
+/// authority.insert(Permission::Read);
+/// authority.insert(Permission::Write);
+///
+/// let mut requested = EnumSet::new();
+/// requested.insert(Permission::Read);
+/// let granted = Granted::new(requested, &authority).unwrap();
+/// assert!(granted.contains(Permission::Read));
+///
+/// requested.insert(Permission::Admin);
+/// assert!(Granted::new(requested, &authority).is_none());
+/// ```
+pub struct Granted<E> {
+    set: EnumSet<E>,
+}
+
+impl<E: CLike> Granted<E> {
+    /// Returns `requested` as a `Granted` if it is a subset of `authority`.
+    pub fn new(requested: EnumSet<E>, authority: &EnumSet<E>) -> Option<Self> {
+        if requested.is_subset(authority) {
+            Some(Granted { set: requested })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a `Granted` holding every element of `authority`.
+    pub fn all_of(authority: &EnumSet<E>) -> Self {
+        Granted { set: EnumSet::new_with_bits(authority.bits) }
+    }
+
+    /// Returns `requested` as a `Granted` if it is a subset of the set.
+    pub fn narrow(&self, requested: EnumSet<E>) -> Option<Self> {
+        Self::new(requested, &self.set)
+    }
+
+    /// Returns the elements of the set which are also in `other`.
+    pub fn restrict(&self, other: &EnumSet<E>) -> Self {
+        Granted { set: EnumSet::new_with_bits(self.set.bits & other.bits) }
+    }
+
+    /// Removes a value from the set. Returns `true` if the value was present.
+    pub fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        self.set.remove(value)
+    }
+
+    /// Returns `true` if the set contains `value`.
+    pub fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        self.set.contains(value)
+    }
+
+    /// Returns the elements of the set.
+    pub fn get(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.set.bits)
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for Granted<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_tuple("Granted").field(&self.set).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::set;
+    use tests::Foo::*;
+
+    use super::Granted;
+
+    #[test]
+    fn test_new() {
+        let authority = set(&[A, B]);
+        assert_eq!(Some(set(&[A])), Granted::new(set(&[A]), &authority).map(|g| g.get()));
+        assert_eq!(Some(set(&[])), Granted::new(set(&[]), &authority).map(|g| g.get()));
+        assert!(Granted::new(set(&[A, C]), &authority).is_none());
+        assert_eq!(authority, Granted::all_of(&authority).get());
+    }
+
+    #[test]
+    fn test_shrink() {
+        let mut granted = Granted::all_of(&set(&[A, B, C]));
+        assert_eq!(set(&[B, C]), granted.restrict(&set(&[B, C])).get());
+        assert!(granted.remove(B));
+        assert!(!granted.contains(B));
+        assert!(granted.narrow(set(&[A, B])).is_none());
+        assert_eq!(Some(set(&[C])), granted.narrow(set(&[C])).map(|g| g.get()));
+    }
+}
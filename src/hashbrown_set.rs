@@ -0,0 +1,55 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::hash::{BuildHasher, Hash};
+
+use hashbrown::HashSet;
+
+use {CLike, EnumSet};
+
+impl<E, S> From<EnumSet<E>> for HashSet<E, S>
+    where E: CLike + Hash + Eq,
+          S: BuildHasher + Default
+{
+    fn from(set: EnumSet<E>) -> Self {
+        set.iter().collect()
+    }
+}
+
+impl<E: CLike, S> From<HashSet<E, S>> for EnumSet<E> {
+    fn from(elements: HashSet<E, S>) -> Self {
+        elements.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hashbrown::HashSet;
+
+    use tests::Foo;
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_conversions() {
+        let set: EnumSet<Foo> = [C, A].iter().collect();
+        let hash_set: HashSet<Foo> = set.into();
+        assert_eq!(2, hash_set.len());
+        assert!(hash_set.contains(&A) && hash_set.contains(&C));
+        assert_eq!(set, EnumSet::from(hash_set));
+
+        let mut hash_set = HashSet::new();
+        hash_set.insert(C);
+        hash_set.insert(B);
+        hash_set.insert(C);
+        assert_eq!([B, C].iter().collect::<EnumSet<_>>(), EnumSet::from(hash_set));
+    }
+}
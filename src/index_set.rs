@@ -0,0 +1,54 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::hash::{BuildHasher, Hash};
+
+use indexmap::IndexSet;
+
+use {CLike, EnumSet};
+
+impl<E, S> From<EnumSet<E>> for IndexSet<E, S>
+    where E: CLike + Hash + Eq,
+          S: BuildHasher + Default
+{
+    fn from(set: EnumSet<E>) -> Self {
+        set.iter().collect()
+    }
+}
+
+impl<E: CLike, S> From<IndexSet<E, S>> for EnumSet<E> {
+    fn from(elements: IndexSet<E, S>) -> Self {
+        elements.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexSet;
+
+    use tests::Foo;
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_conversions() {
+        let set: EnumSet<Foo> = [C, A].iter().collect();
+        let index_set: IndexSet<Foo> = set.into();
+        assert_eq!(vec![A, C], index_set.iter().cloned().collect::<Vec<_>>());
+        assert_eq!(set, EnumSet::from(index_set));
+
+        let mut index_set = IndexSet::new();
+        index_set.insert(C);
+        index_set.insert(B);
+        index_set.insert(C);
+        assert_eq!([B, C].iter().collect::<EnumSet<_>>(), EnumSet::from(index_set));
+    }
+}
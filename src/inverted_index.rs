@@ -0,0 +1,160 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::borrow;
+use std::iter;
+use std::marker::PhantomData;
+
+use {bit, CLike, EnumSet};
+
+/// An index from variants to the records containing them.
+///
+/// Records are sets, numbered in the order they were added to the index.
+pub struct InvertedIndex<E> {
+    records: Vec<u32>,
+    // The indices of the records containing each variant, in increasing
+    // order.
+    postings: Vec<Vec<usize>>,
+    phantom: PhantomData<E>,
+}
+
+impl<E: CLike> InvertedIndex<E> {
+    /// Returns an empty `InvertedIndex`.
+    pub fn new() -> Self {
+        InvertedIndex { records: Vec::new(), postings: vec![Vec::new(); 32], phantom: PhantomData }
+    }
+
+    /// Adds a record to the index, returning its index.
+    pub fn push(&mut self, record: EnumSet<E>) -> usize {
+        let index = self.records.len();
+        self.records.push(record.bits);
+        for (variant, postings) in self.postings.iter_mut().enumerate() {
+            if record.bits & (1 << variant) != 0 {
+                postings.push(index);
+            }
+        }
+        index
+    }
+
+    /// Returns the number of records.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Checks if the index has no records.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Returns the record at `index`.
+    pub fn get(&self, index: usize) -> Option<EnumSet<E>> {
+        self.records.get(index).map(|&bits| EnumSet::new_with_bits(bits))
+    }
+
+    /// Returns the indices of the records containing `value`, in increasing
+    /// order.
+    pub fn records_with<V: borrow::Borrow<E>>(&self, value: V) -> &[usize] {
+        &self.postings[bit(value.borrow()).trailing_zeros() as usize]
+    }
+
+    /// Returns the indices of the records containing every element of `set`,
+    /// in increasing order.
+    ///
+    /// Every record is returned if `set` is empty.
+    pub fn records_with_all(&self, set: &EnumSet<E>) -> Vec<usize> {
+        let shortest = self.postings
+                           .iter()
+                           .enumerate()
+                           .filter(|&(variant, _)| set.bits & (1 << variant) != 0)
+                           .map(|(_, postings)| postings)
+                           .min_by_key(|postings| postings.len());
+        match shortest {
+            Some(postings) => {
+                postings.iter()
+                        .cloned()
+                        .filter(|&index| self.records[index] & set.bits == set.bits)
+                        .collect()
+            }
+            None => (0..self.records.len()).collect(),
+        }
+    }
+
+    /// Returns the indices of the records containing any element of `set`,
+    /// in increasing order.
+    pub fn records_with_any(&self, set: &EnumSet<E>) -> Vec<usize> {
+        let mut result: Vec<usize> = self.postings
+                                         .iter()
+                                         .enumerate()
+                                         .filter(|&(variant, _)| set.bits & (1 << variant) != 0)
+                                         .flat_map(|(_, postings)| postings.iter().cloned())
+                                         .collect();
+        result.sort_unstable();
+        result.dedup();
+        result
+    }
+}
+
+impl<E: CLike> Default for InvertedIndex<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: CLike> iter::FromIterator<EnumSet<E>> for InvertedIndex<E> {
+    fn from_iter<I: IntoIterator<Item = EnumSet<E>>>(iterator: I) -> Self {
+        let mut ret = Self::new();
+        ret.extend(iterator);
+        ret
+    }
+}
+
+impl<E: CLike> Extend<EnumSet<E>> for InvertedIndex<E> {
+    fn extend<I: IntoIterator<Item = EnumSet<E>>>(&mut self, iter: I) {
+        for record in iter {
+            self.push(record);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use super::InvertedIndex;
+
+    #[test]
+    fn test_queries() {
+        let records = [set(&[A, B]), set(&[C]), set(&[A, B, C]), set(&[]), set(&[B])];
+        let index: InvertedIndex<Foo> = records.iter().cloned().collect();
+
+        assert_eq!(5, index.len());
+        assert_eq!(Some(set(&[C])), index.get(1));
+        assert_eq!(None, index.get(5));
+
+        assert_eq!(&[0, 2], index.records_with(A));
+        assert_eq!(&[0, 2, 4], index.records_with(B));
+        assert_eq!(vec![0, 2], index.records_with_all(&set(&[A, B])));
+        assert_eq!(vec![2], index.records_with_all(&set(&[A, C])));
+        assert_eq!(vec![0, 1, 2, 3, 4], index.records_with_all(&set(&[])));
+        assert_eq!(vec![1, 2], index.records_with_any(&set(&[C])));
+        assert_eq!(vec![0, 1, 2, 4], index.records_with_any(&set(&[B, C])));
+        assert!(index.records_with_any(&set(&[])).is_empty());
+    }
+
+    #[test]
+    fn test_push() {
+        let mut index = InvertedIndex::new();
+        assert!(index.is_empty());
+        assert_eq!(0, index.push(set(&[A])));
+        assert_eq!(1, index.push(set(&[A])));
+        assert_eq!(&[0, 1], index.records_with(A));
+    }
+}
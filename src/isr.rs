@@ -0,0 +1,150 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::borrow;
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+
+use critical_section::{self, Mutex};
+
+use {bit, CLike, EnumSet};
+
+/// A set which can be shared between interrupt handlers and the main loop.
+///
+/// Every operation runs inside `critical_section::with`, so it works on
+/// targets without atomic read-modify-write instructions. The set can be
+/// created in a `static`, as `new` is a `const fn`.
+///
+/// ```ignore
+/// static PENDING: IsrEnumSet<Event> = IsrEnumSet::new();
+///
+/// fn on_uart_interrupt() {
+///     PENDING.insert(Event::UartRx);
+/// }
+///
+/// fn main_loop() {
+///     for event in PENDING.take() {
+///         handle(event);
+///     }
+/// }
+/// ```
+pub struct IsrEnumSet<E> {
+    bits: Mutex<Cell<u32>>,
+    phantom: PhantomData<fn() -> E>,
+}
+
+impl<E> IsrEnumSet<E> {
+    /// Returns an empty set.
+    pub const fn new() -> Self {
+        IsrEnumSet { bits: Mutex::new(Cell::new(0)), phantom: PhantomData }
+    }
+}
+
+impl<E: CLike> IsrEnumSet<E> {
+    fn modify<F: FnOnce(u32) -> u32>(&self, f: F) -> u32 {
+        critical_section::with(|cs| {
+            let bits = self.bits.borrow(cs);
+            let old = bits.get();
+            bits.set(f(old));
+            old
+        })
+    }
+
+    /// Returns a copy of the set.
+    pub fn get(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(critical_section::with(|cs| self.bits.borrow(cs).get()))
+    }
+
+    /// Adds the given value to the set.
+    ///
+    /// Returns `true` if the value was not already present in the set.
+    pub fn insert(&self, value: E) -> bool {
+        let value = bit(&value);
+        self.modify(|bits| bits | value) & value == 0
+    }
+
+    /// Removes a value from the set.
+    ///
+    /// Returns `true` if the value was present in the set.
+    pub fn remove<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        let value = bit(value.borrow());
+        self.modify(|bits| bits & !value) & value != 0
+    }
+
+    /// Returns `true` if the set contains the given value.
+    pub fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        self.get().contains(value)
+    }
+
+    /// Adds every element of `other` to the set.
+    pub fn insert_all(&self, other: &EnumSet<E>) {
+        self.modify(|bits| bits | other.bits);
+    }
+
+    /// Empties the set, returning its previous contents.
+    pub fn take(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.modify(|_| 0))
+    }
+
+    /// Replaces the set with `other`, returning its previous contents.
+    pub fn replace(&self, other: EnumSet<E>) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.modify(|_| other.bits))
+    }
+}
+
+impl<E> Default for IsrEnumSet<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for IsrEnumSet<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_tuple("IsrEnumSet").field(&self.get()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use super::IsrEnumSet;
+
+    static PENDING: IsrEnumSet<Foo> = IsrEnumSet::new();
+
+    #[test]
+    fn test_operations() {
+        let e = IsrEnumSet::new();
+        assert!(e.insert(A));
+        assert!(!e.insert(A));
+        assert!(e.contains(A));
+        assert!(!e.remove(B));
+        assert!(e.remove(A));
+        e.insert_all(&set(&[B, C]));
+        assert_eq!(set(&[B, C]), e.replace(set(&[A])));
+        assert_eq!(set(&[A]), e.take());
+        assert!(e.get().is_empty());
+    }
+
+    #[test]
+    fn test_static() {
+        let handles: Vec<_> = [A, B, C].iter().map(|&value| {
+            thread::spawn(move || { PENDING.insert(value); })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(set(&[A, B, C]), PENDING.take());
+    }
+}
@@ -0,0 +1,272 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A set recording its mutations, with undo and redo.
+
+use std::borrow;
+use std::fmt;
+use std::slice;
+
+use {bit, CLike, EnumSet};
+
+/// A mutating operation on a set.
+pub enum Operation<E> {
+    /// Adds a value to the set.
+    Insert(E),
+    /// Removes a value from the set.
+    Remove(E),
+    /// Removes all elements from the set.
+    Clear,
+    /// Adds every element of a set.
+    Union(EnumSet<E>),
+    /// Removes the elements not in a set.
+    Intersection(EnumSet<E>),
+    /// Removes the elements of a set.
+    Difference(EnumSet<E>),
+    /// Toggles the elements of a set.
+    SymmetricDifference(EnumSet<E>),
+    /// Replaces the contents with a set.
+    Replace(EnumSet<E>),
+}
+
+impl<E: CLike> Operation<E> {
+    /// Applies the operation to `set`.
+    pub fn apply(&self, set: &mut EnumSet<E>) {
+        set.bits = match *self {
+            Operation::Insert(ref value) => set.bits | bit(value),
+            Operation::Remove(ref value) => set.bits & !bit(value),
+            Operation::Clear => 0,
+            Operation::Union(ref other) => set.bits | other.bits,
+            Operation::Intersection(ref other) => set.bits & other.bits,
+            Operation::Difference(ref other) => set.bits & !other.bits,
+            Operation::SymmetricDifference(ref other) => set.bits ^ other.bits,
+            Operation::Replace(ref other) => other.bits,
+        };
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for Operation<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Operation::Insert(ref value) => fmt.debug_tuple("Insert").field(value).finish(),
+            Operation::Remove(ref value) => fmt.debug_tuple("Remove").field(value).finish(),
+            Operation::Clear => fmt.write_str("Clear"),
+            Operation::Union(ref other) => fmt.debug_tuple("Union").field(other).finish(),
+            Operation::Intersection(ref other) => {
+                fmt.debug_tuple("Intersection").field(other).finish()
+            }
+            Operation::Difference(ref other) => {
+                fmt.debug_tuple("Difference").field(other).finish()
+            }
+            Operation::SymmetricDifference(ref other) => {
+                fmt.debug_tuple("SymmetricDifference").field(other).finish()
+            }
+            Operation::Replace(ref other) => fmt.debug_tuple("Replace").field(other).finish(),
+        }
+    }
+}
+
+/// A set which records every operation applied to it.
+///
+/// Operations can be undone and redone, and the operations which are
+/// currently applied can be replayed onto another set. Applying a new
+/// operation discards the operations which were undone.
+pub struct Journal<E> {
+    set: EnumSet<E>,
+    // Every operation with the bits of the set before it was applied. Only
+    // the first `applied` operations are applied to `set`.
+    operations: Vec<(Operation<E>, u32)>,
+    applied: usize,
+}
+
+impl<E: CLike> Journal<E> {
+    /// Returns a `Journal` starting from `set`, with no recorded operations.
+    pub fn new(set: EnumSet<E>) -> Self {
+        Journal { set, operations: Vec::new(), applied: 0 }
+    }
+
+    /// Returns the current contents of the set.
+    pub fn get(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.set.bits)
+    }
+
+    /// Applies `operation` to the set and records it.
+    pub fn apply(&mut self, operation: Operation<E>) {
+        self.operations.truncate(self.applied);
+        let before = self.set.bits;
+        operation.apply(&mut self.set);
+        self.operations.push((operation, before));
+        self.applied += 1;
+    }
+
+    /// Adds the given value to the set.
+    ///
+    /// Returns `true` if the value was not already present in the set.
+    pub fn insert(&mut self, value: E) -> bool {
+        let result = !self.set.contains(&value);
+        self.apply(Operation::Insert(value));
+        result
+    }
+
+    /// Removes a value from the set.
+    ///
+    /// Returns `true` if the value was present in the set.
+    pub fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        let value = value.borrow();
+        let result = self.set.contains(value);
+        // Safe because `value` is a variant of `E`.
+        let value = unsafe { E::from_u32(value.to_u32()) };
+        self.apply(Operation::Remove(value));
+        result
+    }
+
+    /// Removes all elements from the set.
+    pub fn clear(&mut self) {
+        self.apply(Operation::Clear);
+    }
+
+    /// Returns `true` if there is an operation to undo.
+    pub fn can_undo(&self) -> bool {
+        self.applied > 0
+    }
+
+    /// Returns `true` if there is an operation to redo.
+    pub fn can_redo(&self) -> bool {
+        self.applied < self.operations.len()
+    }
+
+    /// Reverts the last applied operation.
+    ///
+    /// Returns `false` if there was no operation to undo.
+    pub fn undo(&mut self) -> bool {
+        if !self.can_undo() {
+            return false;
+        }
+        self.applied -= 1;
+        self.set.bits = self.operations[self.applied].1;
+        true
+    }
+
+    /// Applies the last undone operation again.
+    ///
+    /// Returns `false` if there was no operation to redo.
+    pub fn redo(&mut self) -> bool {
+        if !self.can_redo() {
+            return false;
+        }
+        self.operations[self.applied].0.apply(&mut self.set);
+        self.applied += 1;
+        true
+    }
+
+    /// Returns an iterator over the applied operations, oldest first.
+    pub fn operations(&self) -> Operations<'_, E> {
+        Operations { inner: self.operations[..self.applied].iter() }
+    }
+
+    /// Applies the applied operations to `target`, oldest first.
+    pub fn replay(&self, target: &mut EnumSet<E>) {
+        for operation in self.operations() {
+            operation.apply(target);
+        }
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for Journal<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Journal")
+           .field("set", &self.set)
+           .field("operations", &self.operations().collect::<Vec<_>>())
+           .finish()
+    }
+}
+
+/// An iterator over the applied operations of a `Journal`.
+pub struct Operations<'a, E: 'a> {
+    inner: slice::Iter<'a, (Operation<E>, u32)>,
+}
+
+impl<'a, E> Iterator for Operations<'a, E> {
+    type Item = &'a Operation<E>;
+
+    fn next(&mut self) -> Option<&'a Operation<E>> {
+        self.inner.next().map(|(operation, _)| operation)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, E> ExactSizeIterator for Operations<'a, E> {}
+
+#[cfg(test)]
+mod tests {
+    use tests::set;
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::{Journal, Operation};
+
+    #[test]
+    fn test_undo_redo() {
+        let mut journal = Journal::new(set(&[A]));
+        assert!(!journal.can_undo());
+        assert!(journal.insert(B));
+        assert!(journal.remove(A));
+        journal.apply(Operation::SymmetricDifference(set(&[B, C])));
+        assert_eq!(set(&[C]), journal.get());
+
+        assert!(journal.undo());
+        assert_eq!(set(&[B]), journal.get());
+        assert!(journal.undo());
+        assert!(journal.undo());
+        assert_eq!(set(&[A]), journal.get());
+        assert!(!journal.undo());
+
+        assert!(journal.redo());
+        assert_eq!(set(&[A, B]), journal.get());
+        assert!(journal.can_redo());
+
+        journal.clear();
+        assert!(!journal.can_redo());
+        assert!(!journal.redo());
+        assert!(journal.get().is_empty());
+        assert_eq!(2, journal.operations().len());
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut journal = Journal::new(EnumSet::new());
+        journal.insert(A);
+        journal.apply(Operation::Union(set(&[B])));
+        journal.apply(Operation::Difference(set(&[C])));
+        journal.apply(Operation::Replace(set(&[C])));
+        journal.undo();
+
+        let mut target = set(&[C]);
+        journal.replay(&mut target);
+        assert_eq!(set(&[A, B]), target);
+
+        let mut target = set(&[C]);
+        journal.apply(Operation::Intersection(set(&[A])));
+        journal.replay(&mut target);
+        assert_eq!(set(&[A]), target);
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut journal = Journal::new(EnumSet::new());
+        journal.insert(A);
+        journal.clear();
+        assert_eq!("Journal { set: {}, operations: [Insert(A), Clear] }",
+                   format!("{:?}", journal));
+    }
+}
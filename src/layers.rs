@@ -0,0 +1,180 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sets built from a stack of overriding layers.
+//!
+//! Every layer adds and removes some variants, and later layers override
+//! earlier ones. This models configuration such as defaults, followed by
+//! environment settings, followed by per-user overrides.
+
+use std::borrow;
+use std::fmt;
+use std::slice;
+
+use {bit, CLike, EnumSet};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+/// A single layer of a `LayeredSet`.
+///
+/// A variant in both `adds` and `removes` is removed.
+pub struct Layer<E> {
+    /// The variants enabled by this layer.
+    pub adds: EnumSet<E>,
+    /// The variants disabled by this layer.
+    pub removes: EnumSet<E>,
+}
+
+impl<E: CLike> Layer<E> {
+    /// Returns a layer adding `adds` and removing `removes`.
+    pub fn new(adds: EnumSet<E>, removes: EnumSet<E>) -> Self {
+        Layer { adds, removes }
+    }
+
+    /// Applies the layer on top of `base`.
+    pub fn apply(&self, base: EnumSet<E>) -> EnumSet<E> {
+        let adds = EnumSet::new_with_bits(self.adds.bits);
+        let removes = EnumSet::new_with_bits(self.removes.bits);
+        EnumSet::resolve(adds, removes, base)
+    }
+
+    fn decides(&self, value: &E) -> bool {
+        ((self.adds.bits | self.removes.bits) & bit(value)) != 0
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for Layer<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Layer")
+           .field("adds", &self.adds)
+           .field("removes", &self.removes)
+           .finish()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+/// An ordered stack of layers, from lowest to highest precedence.
+pub struct LayeredSet<E> {
+    layers: Vec<Layer<E>>,
+}
+
+impl<E: CLike> LayeredSet<E> {
+    /// Returns a `LayeredSet` without any layers.
+    pub fn new() -> Self {
+        LayeredSet { layers: Vec::new() }
+    }
+
+    /// Pushes a layer with the highest precedence, returning its index.
+    pub fn push(&mut self, layer: Layer<E>) -> usize {
+        self.layers.push(layer);
+        self.layers.len() - 1
+    }
+
+    /// Removes the layer with the highest precedence and returns it.
+    pub fn pop(&mut self) -> Option<Layer<E>> {
+        self.layers.pop()
+    }
+
+    /// Returns the layer at `index`.
+    pub fn layer(&self, index: usize) -> Option<&Layer<E>> {
+        self.layers.get(index)
+    }
+
+    /// Returns a mutable reference to the layer at `index`.
+    pub fn layer_mut(&mut self, index: usize) -> Option<&mut Layer<E>> {
+        self.layers.get_mut(index)
+    }
+
+    /// Returns the number of layers.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Checks if there are no layers.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Returns an iterator over the layers, from lowest to highest
+    /// precedence.
+    pub fn iter(&self) -> slice::Iter<'_, Layer<E>> {
+        self.layers.iter()
+    }
+
+    /// Returns the set resulting from applying every layer in order to an
+    /// empty set.
+    pub fn effective(&self) -> EnumSet<E> {
+        self.layers.iter().fold(EnumSet::new(), |set, layer| layer.apply(set))
+    }
+
+    /// Returns the index of the layer which decided whether `value` is in the
+    /// effective set, or `None` if no layer mentions it.
+    pub fn decided_by<V: borrow::Borrow<E>>(&self, value: V) -> Option<usize> {
+        let value = value.borrow();
+        self.layers.iter().rposition(|layer| layer.decides(value))
+    }
+}
+
+impl<E: CLike> Default for LayeredSet<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for LayeredSet<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self).finish()
+    }
+}
+
+impl<'a, E: CLike> IntoIterator for &'a LayeredSet<E> {
+    type Item = &'a Layer<E>;
+    type IntoIter = slice::Iter<'a, Layer<E>>;
+    fn into_iter(self) -> slice::Iter<'a, Layer<E>> { self.iter() }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::set;
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::{Layer, LayeredSet};
+
+    #[test]
+    fn test_effective() {
+        let mut layers = LayeredSet::new();
+        assert!(layers.effective().is_empty());
+
+        assert_eq!(0, layers.push(Layer::new(set(&[A, B]), EnumSet::new())));
+        assert_eq!(1, layers.push(Layer::new(set(&[C]), set(&[A]))));
+        assert_eq!(set(&[B, C]), layers.effective());
+
+        assert_eq!(2, layers.push(Layer::new(set(&[A]), set(&[A]))));
+        assert_eq!(set(&[B, C]), layers.effective());
+
+        layers.layer_mut(2).unwrap().removes = EnumSet::new();
+        assert_eq!(set(&[A, B, C]), layers.effective());
+
+        layers.pop();
+        assert_eq!(2, layers.len());
+        assert_eq!(set(&[B, C]), layers.effective());
+    }
+
+    #[test]
+    fn test_decided_by() {
+        let mut layers = LayeredSet::new();
+        layers.push(Layer::new(set(&[A, B]), EnumSet::new()));
+        layers.push(Layer::new(EnumSet::new(), set(&[A])));
+
+        assert_eq!(Some(1), layers.decided_by(A));
+        assert_eq!(Some(0), layers.decided_by(B));
+        assert_eq!(None, layers.decided_by(C));
+    }
+}
@@ -13,11 +13,185 @@
 //! This module defines a container which uses an efficient bit mask
 //! representation to hold C-like enum variants.
 
+#[cfg(feature = "arrow")]
+extern crate arrow_array;
+#[cfg(feature = "bson")]
+extern crate bson;
+#[cfg(feature = "critical-section")]
+extern crate critical_section;
+#[cfg(feature = "dialoguer")]
+extern crate dialoguer;
+#[cfg(feature = "hashbrown")]
+extern crate hashbrown;
+#[cfg(feature = "indexmap")]
+extern crate indexmap;
+#[cfg(feature = "metrics")]
+extern crate metrics;
+#[cfg(feature = "modular-bitfield")]
+extern crate modular_bitfield;
+#[cfg(feature = "pyo3")]
+extern crate pyo3;
+#[cfg(feature = "rand")]
+extern crate rand;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "rocket")]
+extern crate rocket;
+#[cfg(feature = "rusqlite")]
+extern crate rusqlite;
+#[cfg(feature = "sea-orm")]
+extern crate sea_orm;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "smallvec")]
+extern crate smallvec;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+#[cfg(feature = "ufmt")]
+extern crate ufmt;
+#[cfg(feature = "uniffi")]
+extern crate uniffi_core;
+#[cfg(feature = "zeroize")]
+extern crate zeroize;
+#[cfg(feature = "zvariant")]
+extern crate zvariant;
+#[cfg(all(test, feature = "serde"))]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
+use std::borrow;
+use std::cmp;
+use std::collections::{BTreeSet, HashSet};
+use std::error;
 use std::fmt;
+use std::fmt::Write;
 use std::hash;
 use std::marker::PhantomData;
 use std::iter;
+use std::num;
 use std::ops;
+use std::slice;
+
+pub use access::AccessMask;
+pub use any::AnyEnumSet;
+pub use compact::CompactStringError;
+pub use constraints::{ConstraintViolation, Constraints};
+pub use cooccurrence::CooccurrenceMatrix;
+pub use either::Either;
+pub use env::EnvError;
+pub use expr::ExprError;
+#[doc(hidden)]
+pub use flags::__Description;
+#[cfg(feature = "metrics")]
+pub use gauges::SetGauges;
+pub use granted::Granted;
+pub use inverted_index::InvertedIndex;
+#[cfg(feature = "critical-section")]
+pub use isr::IsrEnumSet;
+pub use like::EnumSetLike;
+pub use names::NameCase;
+pub use non_empty::NonEmptyEnumSet;
+pub use open::OpenEnumSet;
+pub use parse::ParseNamesError;
+pub use power_set_map::PowerSetMap;
+pub use scoped::{ScopedCellFlag, ScopedFlag};
+#[cfg(feature = "bson")]
+pub use serde_bson::FromBsonError;
+#[cfg(feature = "tokio")]
+pub use shared::{FlagsReceiver, SharedFlags};
+pub use sparse::{SparseCLike, SparseEnumSet, SparseIter};
+pub use transition::TransitionTable;
+pub use tri_state::{TriState, TriStateMap};
+pub use versioned::VersionedDecodeError;
+pub use watched::WatchedEnumSet;
+
+pub mod algorithms;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod batch;
+pub mod crdt;
+pub mod journal;
+pub mod layers;
+pub mod power_set_map;
+pub mod register;
+#[cfg(feature = "serde")]
+pub mod serde_auto;
+#[cfg(feature = "serde")]
+pub mod serde_bits;
+#[cfg(feature = "bson")]
+pub mod serde_bson;
+#[cfg(feature = "serde")]
+pub mod serde_bool_map;
+#[cfg(feature = "serde")]
+pub mod serde_lenient;
+#[cfg(feature = "serde")]
+pub mod serde_names;
+pub mod testsuite;
+pub mod timeline;
+pub mod transition;
+mod access;
+mod any;
+#[cfg(feature = "modular-bitfield")]
+mod bitfield;
+mod compact;
+mod constraints;
+mod cooccurrence;
+#[cfg(feature = "zvariant")]
+mod dbus;
+mod either;
+mod env;
+mod expr;
+mod flags;
+#[cfg(feature = "metrics")]
+mod gauges;
+mod granted;
+#[cfg(feature = "hashbrown")]
+mod hashbrown_set;
+#[cfg(feature = "indexmap")]
+mod index_set;
+mod inverted_index;
+#[cfg(feature = "critical-section")]
+mod isr;
+mod like;
+mod names;
+mod non_empty;
+mod open;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod parse;
+#[cfg(feature = "dialoguer")]
+mod prompt;
+#[cfg(feature = "pyo3")]
+mod pyo3_conversions;
+#[cfg(feature = "rand")]
+mod random;
+mod raw;
+#[cfg(feature = "rocket")]
+mod rocket_params;
+mod scoped;
+#[cfg(feature = "sea-orm")]
+mod sea_orm_value;
+#[cfg(feature = "tokio")]
+mod shared;
+#[cfg(feature = "smallvec")]
+mod small_vec;
+mod sparse;
+#[cfg(feature = "rusqlite")]
+mod sqlite;
+mod stable_hash;
+mod static_assert;
+mod stream;
+mod tri_state;
+#[cfg(feature = "ufmt")]
+mod ufmt_format;
+#[cfg(feature = "uniffi")]
+mod uniffi_converter;
+mod versioned;
+mod watched;
+#[cfg(feature = "zeroize")]
+mod zeroizing;
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 /// A specialized set implementation to use enum types.
@@ -28,9 +202,114 @@ pub struct EnumSet<E> {
     phantom: PhantomData<E>,
 }
 
+/// Formats the elements of `set` with `element`, separated by `, `, and
+/// pads the result according to the width, fill and alignment of `fmt`.
+///
+/// The precision of `fmt`, if any, limits the number of elements written,
+/// with `...` standing in for the rest.
+fn fmt_padded<E, F>(set: &EnumSet<E>, fmt: &mut fmt::Formatter, braces: bool, element: F)
+                    -> fmt::Result
+    where E: CLike,
+          F: Fn(&mut String, E) -> fmt::Result
+{
+    let mut out = String::new();
+    if braces {
+        out.push('{');
+    }
+    let limit = fmt.precision().unwrap_or(usize::MAX);
+    for (index, value) in set.iter().enumerate() {
+        if index != 0 {
+            out.push_str(", ");
+        }
+        if index == limit {
+            out.push_str("...");
+            break;
+        }
+        element(&mut out, value)?;
+    }
+    if braces {
+        out.push('}');
+    }
+
+    let padding = fmt.width().map_or(0, |width| width.saturating_sub(out.chars().count()));
+    let (before, after) = match fmt.align() {
+        Some(fmt::Alignment::Right) => (padding, 0),
+        Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+        Some(fmt::Alignment::Left) | None => (0, padding),
+    };
+    let fill = fmt.fill();
+    for _ in 0..before {
+        fmt.write_char(fill)?;
+    }
+    fmt.write_str(&out)?;
+    for _ in 0..after {
+        fmt.write_char(fill)?;
+    }
+    Ok(())
+}
+
+/// Formats the set as `{A, B}`.
+///
+/// Width, fill and alignment apply to the set as a whole, and a precision
+/// limits the number of elements shown. The alternate flag `{:#?}` formats
+/// the set over multiple lines and ignores the other flags.
 impl<E: CLike + fmt::Debug> fmt::Debug for EnumSet<E> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        fmt.debug_set().entries(self).finish()
+        if fmt.alternate() || (fmt.width().is_none() && fmt.precision().is_none()) {
+            return fmt.debug_set().entries(self).finish();
+        }
+        fmt_padded(self, fmt, true, |out, value| {
+            write!(out, "{:?}", value)
+        })
+    }
+}
+
+/// Formats the elements of the set separated by `, `.
+///
+/// Width, fill and alignment apply to the set as a whole, and a precision
+/// limits the number of elements shown.
+impl<E: CLike + fmt::Display> fmt::Display for EnumSet<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt_padded(self, fmt, false, |out, value| {
+            write!(out, "{}", value)
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+/// A `Debug` representation of a set listing the indices of its elements,
+/// returned by `EnumSet::debug_indices`.
+pub struct DebugIndices {
+    bits: u32,
+}
+
+impl fmt::Debug for DebugIndices {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("EnumSet")?;
+        fmt.debug_set().entries((0..32).filter(|index| self.bits & (1 << index) != 0)).finish()
+    }
+}
+
+/// A `Display` representation of a set listing every variant with a marker
+/// showing whether the set contains it, returned by `EnumSet::checklist`.
+pub struct Checklist<E> {
+    bits: u32,
+    phantom: PhantomData<E>,
+}
+
+/// Writes one line per variant, `[x] Name` or `[ ] Name`, or `✓ Name` or
+/// `✗ Name` with the alternate flag (`{:#}`).
+impl<E: Finite + fmt::Debug> fmt::Display for Checklist<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let (yes, no) = if fmt.alternate() { ("\u{2713}", "\u{2717}") } else { ("[x]", "[ ]") };
+        for (index, (_, name)) in names::variants::<E>().enumerate() {
+            if index != 0 {
+                fmt.write_str("\n")?;
+            }
+            let marker = if self.bits & (1 << index) != 0 { yes } else { no };
+            write!(fmt, "{} {}", marker, name)?;
+        }
+        Ok(())
     }
 }
 
@@ -70,33 +349,120 @@ pub trait CLike {
     /// Converts a C-like enum to a `u32`. The value must be `<= 31`.
     fn to_u32(&self) -> u32;
 
-    /// Converts a `u32` to a C-like enum. This method only needs to be safe
-    /// for possible return values of `to_u32` of this trait.
-    unsafe fn from_u32(u32) -> Self;
+    /// Converts a `u32` to a C-like enum.
+    ///
+    /// # Safety
+    ///
+    /// This method only needs to be safe for possible return values of
+    /// `to_u32` of this trait.
+    unsafe fn from_u32(value: u32) -> Self;
+}
+
+/// A `CLike` enum with a known number of variants.
+///
+/// This allows operations which need to know about every variant of the
+/// enum, rather than only the ones stored in a set.
+///
+/// # Safety
+///
+/// `VARIANT_COUNT` must be no more than 32, and `CLike::from_u32` must be
+/// safe to call with every value below `VARIANT_COUNT`.
+pub unsafe trait Finite: CLike {
+    /// The number of variants of the enum.
+    const VARIANT_COUNT: u32;
+
+    /// Former names of variants, which are accepted along with the current
+    /// names when a variant is looked up by name. Each entry is an alias and
+    /// the value of the variant it stands for.
+    ///
+    /// `enum_set_flags!` fills this from the aliases of the variants.
+    const ALIASES: &'static [(&'static str, u32)] = &[];
+
+    /// The case of the names of the variants, applied to their `Debug`
+    /// representations when sets are displayed, parsed or serialized by
+    /// name. Aliases are used as they are.
+    const NAME_CASE: NameCase = NameCase::Unchanged;
+
+    /// Whether names and aliases are looked up ignoring case.
+    const CASE_INSENSITIVE_NAMES: bool = false;
+}
+
+/// A `CLike` enum with a human-readable description of each variant.
+///
+/// `enum_set_flags!` implements this with the doc comments of the variants.
+pub trait Describe: CLike {
+    /// Returns the description of the variant.
+    fn description(&self) -> &'static str;
+}
+
+/// A `CLike` enum with a set of variants enabled by default.
+///
+/// `enum_set_flags!` implements this for enums declared with a `default`
+/// clause. The set is returned by `EnumSet::default_set`, while
+/// `EnumSet::default` stays empty.
+pub trait DefaultSet: CLike + Sized {
+    /// The variants enabled by default.
+    const DEFAULT_SET: EnumSet<Self>;
 }
 
+/// Returns a mask with a bit set for every variant of `E`.
+const fn universe<E: Finite>() -> u32 {
+    if E::VARIANT_COUNT >= 32 {
+        !0
+    } else {
+        (1 << E::VARIANT_COUNT) - 1
+    }
+}
+
+#[inline(always)]
 fn bit<E: CLike>(e: &E) -> u32 {
     let value = e.to_u32();
-    assert!(value < 32, "EnumSet only supports up to {} variants.", 31);
+    if value >= 32 {
+        too_many_variants();
+    }
     1 << value
 }
 
+// Kept out of line so that `bit` stays small enough to be inlined into its
+// callers.
+#[cold]
+#[inline(never)]
+fn too_many_variants() -> ! {
+    panic!("EnumSet only supports up to {} variants.", 31);
+}
+
 impl<E: CLike> EnumSet<E> {
     /// Returns an empty `EnumSet`.
+    #[inline]
     pub fn new() -> Self {
         Self::new_with_bits(0)
     }
 
+    #[inline]
     fn new_with_bits(bits: u32) -> Self {
-        EnumSet { bits: bits, phantom: PhantomData }
+        EnumSet { bits, phantom: PhantomData }
+    }
+
+    // Used by `enum_set_flags!` to declare constants.
+    #[doc(hidden)]
+    pub const fn __from_bits(bits: u32) -> Self {
+        EnumSet { bits, phantom: PhantomData }
+    }
+
+    // Used by `static_assert_disjoint!` and `static_assert_covers!`.
+    #[doc(hidden)]
+    pub const fn __bits(&self) -> u32 {
+        self.bits
     }
 
     /// Returns the number of elements in the set.
+    #[inline]
     pub fn len(&self) -> usize {
         self.bits.count_ones() as usize
     }
 
     /// Checks if the set is empty.
+    #[inline]
     pub fn is_empty(&self) -> bool {
         self.bits == 0
     }
@@ -106,46 +472,141 @@ impl<E: CLike> EnumSet<E> {
         self.bits = 0;
     }
 
+    /// Removes all elements from the set, returning the previous contents.
+    pub fn take(&mut self) -> Self {
+        self.replace_with(Self::new())
+    }
+
+    /// Replaces the contents of the set with `other`, returning the previous
+    /// contents.
+    pub fn replace_with(&mut self, other: Self) -> Self {
+        let old = Self::new_with_bits(self.bits);
+        self.bits = other.bits;
+        old
+    }
+
     /// Returns `true` if the set has no elements in common with `other`.
     ///
     /// This is equivalent to checking for an empty intersection.
+    #[inline]
     pub fn is_disjoint(&self, other: &Self) -> bool {
         (self.bits & other.bits) == 0
     }
 
     /// Returns `true` if the set is a superset of `other`.
+    #[inline]
     pub fn is_superset(&self, other: &Self) -> bool {
         (self.bits & other.bits) == other.bits
     }
 
     /// Returns `true` if the set is a subset of `other`.
+    #[inline]
     pub fn is_subset(&self, other: &Self) -> bool {
         other.is_superset(self)
     }
 
+    /// Compares the set with `other` by inclusion.
+    ///
+    /// Returns `Less` if the set is a proper subset of `other`, `Greater` if
+    /// it is a proper superset, `Equal` if both sets are equal and `None` if
+    /// neither set contains the other.
+    ///
+    /// Note that the `Ord` implementation of `EnumSet` compares the
+    /// underlying bits instead, which is a total order unrelated to inclusion.
+    pub fn subset_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        if self.bits == other.bits {
+            Some(cmp::Ordering::Equal)
+        } else if self.is_subset(other) {
+            Some(cmp::Ordering::Less)
+        } else if self.is_superset(other) {
+            Some(cmp::Ordering::Greater)
+        } else {
+            None
+        }
+    }
+
     /// Returns the union of the set and `other`.
-    pub fn union(&self, other: Self) -> Self {
-        Self::new_with_bits(self.bits | other.bits)
+    ///
+    /// Like the other binary operations, `other` can be passed either by
+    /// value or by reference.
+    #[inline]
+    pub fn union<O: borrow::Borrow<Self>>(&self, other: O) -> Self {
+        Self::new_with_bits(self.bits | other.borrow().bits)
     }
 
     /// Returns the intersection of the set and `other`.
-    pub fn intersection(&self, other: Self) -> Self {
-        Self::new_with_bits(self.bits & other.bits)
+    #[inline]
+    pub fn intersection<O: borrow::Borrow<Self>>(&self, other: O) -> Self {
+        Self::new_with_bits(self.bits & other.borrow().bits)
     }
 
     /// Returns the difference between the set and `other`.
-    pub fn difference(&self, other: Self) -> Self {
-        Self::new_with_bits(self.bits & !other.bits)
+    #[inline]
+    pub fn difference<O: borrow::Borrow<Self>>(&self, other: O) -> Self {
+        Self::new_with_bits(self.bits & !other.borrow().bits)
     }
 
     /// Returns the symmetric difference between the set and `other`.
-    pub fn symmetric_difference(&self, other: Self) -> Self {
-        Self::new_with_bits(self.bits ^ other.bits)
+    #[inline]
+    pub fn symmetric_difference<O: borrow::Borrow<Self>>(&self, other: O) -> Self {
+        Self::new_with_bits(self.bits ^ other.borrow().bits)
+    }
+
+    /// Returns `base` with the elements of `allow` added and the elements of
+    /// `deny` removed.
+    ///
+    /// An element in both `allow` and `deny` is denied.
+    pub fn resolve(allow: Self, deny: Self, base: Self) -> Self {
+        Self::new_with_bits((base.bits | allow.bits) & !deny.bits)
+    }
+
+    /// Returns the number of elements the set has in common with `other`.
+    pub fn overlap_len(&self, other: &Self) -> usize {
+        (self.bits & other.bits).count_ones() as usize
+    }
+
+    /// Returns the number of elements contained in exactly one of the set
+    /// and `other`.
+    pub fn hamming_distance(&self, other: &Self) -> usize {
+        (self.bits ^ other.bits).count_ones() as usize
+    }
+
+    /// Returns the size of the intersection divided by the size of the union
+    /// of the set and `other`.
+    ///
+    /// Two empty sets are considered identical, with an index of `1.0`.
+    pub fn jaccard_index(&self, other: &Self) -> f64 {
+        let union = (self.bits | other.bits).count_ones();
+        if union == 0 {
+            return 1.0;
+        }
+        f64::from((self.bits & other.bits).count_ones()) / f64::from(union)
+    }
+
+    /// Returns the sum of the weights of the set's elements, where
+    /// `weights[i]` is the weight of the variant whose `to_u32` is `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights` has no entry for an element of the set.
+    pub fn score(&self, weights: &[i64]) -> i64 {
+        raw::score(self.bits, weights)
+    }
+
+    /// Returns the sum of the weights of the set's elements, like `score`
+    /// but with floating-point weights.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights` has no entry for an element of the set.
+    pub fn score_f64(&self, weights: &[f64]) -> f64 {
+        raw::score_f64(self.bits, weights)
     }
 
     /// Adds the given value to the set.
     ///
     /// Returns `true` if the value was not already present in the set.
+    #[inline]
     pub fn insert(&mut self, value: E) -> bool {
         let result = !self.contains(&value);
         self.bits |= bit(&value);
@@ -155,26 +616,448 @@ impl<E: CLike> EnumSet<E> {
     /// Removes a value from the set.
     ///
     /// Returns `true` if the value was present in the set.
-    pub fn remove(&mut self, value: &E) -> bool {
-        let result = self.contains(value);
-        self.bits &= !bit(value);
+    ///
+    /// The value can be passed either by value or by reference.
+    #[inline]
+    pub fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        let value = bit(value.borrow());
+        let result = (self.bits & value) != 0;
+        self.bits &= !value;
         result
     }
 
     /// Returns `true` if the set contains the given value.
-    pub fn contains(&self, value: &E) -> bool {
-        (self.bits & bit(value)) != 0
+    ///
+    /// The value can be passed either by value or by reference.
+    #[inline]
+    pub fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        (self.bits & bit(value.borrow())) != 0
+    }
+
+    /// Adds the given member to the set.
+    ///
+    /// Returns `true` if the member was not already present in the set.
+    #[inline]
+    pub fn insert_member(&mut self, member: Member<E>) -> bool {
+        let result = (self.bits & member.bit) == 0;
+        self.bits |= member.bit;
+        result
+    }
+
+    /// Removes a member from the set.
+    ///
+    /// Returns `true` if the member was present in the set.
+    #[inline]
+    pub fn remove_member(&mut self, member: Member<E>) -> bool {
+        let result = (self.bits & member.bit) != 0;
+        self.bits &= !member.bit;
+        result
+    }
+
+    /// Returns `true` if the set contains the given member.
+    #[inline]
+    pub fn contains_member(&self, member: Member<E>) -> bool {
+        (self.bits & member.bit) != 0
+    }
+
+    /// Returns `true` if the set contains every one of `values`.
+    ///
+    /// `values` can be a slice, an array or any other iterable over values
+    /// or references.
+    pub fn contains_all<I>(&self, values: I) -> bool
+        where I: IntoIterator,
+              I::Item: borrow::Borrow<E>
+    {
+        values.into_iter().all(|value| self.contains(value))
+    }
+
+    /// Returns `true` if the set contains any one of `values`.
+    ///
+    /// `values` can be a slice, an array or any other iterable over values
+    /// or references.
+    pub fn contains_any<I>(&self, values: I) -> bool
+        where I: IntoIterator,
+              I::Item: borrow::Borrow<E>
+    {
+        values.into_iter().any(|value| self.contains(value))
+    }
+
+    /// Returns the first element of the set after `value`, wrapping around
+    /// to the first element of the set.
+    ///
+    /// Returns `value` itself if it is the only element, and `None` if the
+    /// set is empty. `value` does not need to be in the set.
+    pub fn next_after<V: borrow::Borrow<E>>(&self, value: V) -> Option<E> {
+        let index = bit(value.borrow()).trailing_zeros();
+        // Safe because of the invariant that only valid bits are set.
+        raw::next_after(self.bits, index).map(|index| unsafe { E::from_u32(index) })
+    }
+
+    /// Returns the last element of the set before `value`, wrapping around
+    /// to the last element of the set.
+    ///
+    /// Returns `value` itself if it is the only element, and `None` if the
+    /// set is empty. `value` does not need to be in the set.
+    pub fn prev_before<V: borrow::Borrow<E>>(&self, value: V) -> Option<E> {
+        let index = bit(value.borrow()).trailing_zeros();
+        // Safe because of the invariant that only valid bits are set.
+        raw::prev_before(self.bits, index).map(|index| unsafe { E::from_u32(index) })
+    }
+
+    /// Exchanges the membership of `a` and `b`, so that the set contains `a`
+    /// if and only if it contained `b` before, and vice versa.
+    pub fn swap<A: borrow::Borrow<E>, B: borrow::Borrow<E>>(&mut self, a: A, b: B) {
+        let (a, b) = (bit(a.borrow()), bit(b.borrow()));
+        if (self.bits & a == 0) != (self.bits & b == 0) {
+            self.bits ^= a | b;
+        }
+    }
+
+    /// Relabels the set's elements, replacing the variant whose `to_u32`
+    /// is `i` by `mapping[i]`.
+    ///
+    /// Elements mapped to the same variant are merged into one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mapping` has no entry for an element of the set.
+    pub fn permute(&mut self, mapping: &[E]) {
+        let mut bits = self.bits;
+        let mut result = 0;
+        while bits != 0 {
+            result |= bit(&mapping[bits.trailing_zeros() as usize]);
+            bits &= bits - 1;
+        }
+        self.bits = result;
+    }
+
+    /// Returns the union of the sets `f` maps the set's elements to.
+    pub fn flat_map<F: CLike, M: FnMut(E) -> EnumSet<F>>(&self, mut f: M) -> EnumSet<F> {
+        let mut bits = 0;
+        for element in self.iter() {
+            bits |= f(element).bits;
+        }
+        EnumSet::new_with_bits(bits)
+    }
+
+    /// Returns the first element, in increasing order of value, for which
+    /// `predicate` returns `true`.
+    #[inline]
+    pub fn find<P: FnMut(E) -> bool>(&self, mut predicate: P) -> Option<E> {
+        let mut bits = self.bits;
+        while bits != 0 {
+            // Safe because of the invariant that only valid bits are set.
+            let value = unsafe { E::from_u32(bits.trailing_zeros()) };
+            if predicate(value) {
+                // Safe for the same reason.
+                return Some(unsafe { E::from_u32(bits.trailing_zeros()) });
+            }
+            bits &= bits - 1;
+        }
+        None
+    }
+
+    /// Returns `true` if `predicate` returns `true` for any element.
+    #[inline]
+    pub fn any_match<P: FnMut(E) -> bool>(&self, predicate: P) -> bool {
+        self.find(predicate).is_some()
+    }
+
+    /// Returns `true` if `predicate` returns `true` for every element, which
+    /// is the case for the empty set.
+    ///
+    /// This is not called `all` because `EnumSet::all` returns the set of
+    /// every variant.
+    #[inline]
+    pub fn all_match<P: FnMut(E) -> bool>(&self, mut predicate: P) -> bool {
+        self.find(|value| !predicate(value)).is_none()
+    }
+
+    /// Returns the set together with everything its elements depend on,
+    /// directly or transitively.
+    ///
+    /// `deps[i]` holds the direct dependencies of the variant whose `to_u32`
+    /// is `i`. Variants without an entry in `deps` have no dependencies.
+    pub fn closure(&self, deps: &[EnumSet<E>]) -> EnumSet<E> {
+        let mut result = self.bits;
+        let mut pending = self.bits;
+        while pending != 0 {
+            let index = pending.trailing_zeros() as usize;
+            pending &= pending - 1;
+            let added = deps.get(index).map_or(0, |deps| deps.bits & !result);
+            result |= added;
+            pending |= added;
+        }
+        EnumSet::new_with_bits(result)
+    }
+
+    /// Returns a `Debug` representation of the set which lists the indices
+    /// of its elements, such as `EnumSet{0, 5}`.
+    ///
+    /// Unlike the `Debug` implementation of `EnumSet` itself, this does not
+    /// require `E: Debug`, so it can be used to implement `Debug` for types
+    /// holding sets of enums which do not implement it.
+    pub fn debug_indices(&self) -> DebugIndices {
+        DebugIndices { bits: self.bits }
     }
 
     /// Returns an iterator over the set's elements.
+    #[inline]
     pub fn iter(&self) -> Iter<E> {
         Iter { index: 0, bits: self.bits, phantom: PhantomData }
     }
+
+    /// Returns an iterator over the set's elements in the order in which
+    /// they appear in `priority`.
+    ///
+    /// Elements which do not appear in `priority` are yielded afterwards, in
+    /// increasing order of their discriminants.
+    pub fn iter_ordered_by<'a>(&self, priority: &'a [E]) -> OrderedIter<'a, E> {
+        OrderedIter { order: priority.iter(), bits: self.bits }
+    }
+
+    /// Returns an iterator over the elements of the set or `other`.
+    ///
+    /// This mirrors `HashSet::union`.
+    pub fn union_iter(&self, other: &Self) -> Iter<E> {
+        Iter { index: 0, bits: self.bits | other.bits, phantom: PhantomData }
+    }
+
+    /// Returns an iterator over the elements of both the set and `other`.
+    ///
+    /// This mirrors `HashSet::intersection`.
+    pub fn intersection_iter(&self, other: &Self) -> Iter<E> {
+        Iter { index: 0, bits: self.bits & other.bits, phantom: PhantomData }
+    }
+
+    /// Returns an iterator over the elements of the set which are not in
+    /// `other`.
+    ///
+    /// This mirrors `HashSet::difference`.
+    pub fn difference_iter(&self, other: &Self) -> Iter<E> {
+        Iter { index: 0, bits: self.bits & !other.bits, phantom: PhantomData }
+    }
+
+    /// Returns an iterator over the elements of either the set or `other`,
+    /// but not both.
+    ///
+    /// This mirrors `HashSet::symmetric_difference`.
+    pub fn symmetric_difference_iter(&self, other: &Self) -> Iter<E> {
+        Iter { index: 0, bits: self.bits ^ other.bits, phantom: PhantomData }
+    }
+
+    /// Returns an iterator over every subset of the set, including the empty
+    /// set and the set itself, in increasing order of their bits.
+    pub fn subsets(&self) -> Between<E> {
+        Between::new(0, self.bits)
+    }
+
+    /// Returns an iterator over every subset of the set in increasing order
+    /// of their lengths, starting with the empty set.
+    ///
+    /// Subsets of the same length are yielded in lexicographic order of
+    /// their elements' positions.
+    pub fn subsets_by_len(&self) -> SubsetsByLen<E> {
+        SubsetsByLen { elements: self.bits, len: 0, next: Some(0), phantom: PhantomData }
+    }
+
+    /// Returns an iterator over every set which contains the set and is
+    /// contained in `universe`, in increasing order of their bits.
+    ///
+    /// The iterator is empty if the set is not a subset of `universe`.
+    pub fn supersets_within(&self, universe: &Self) -> Between<E> {
+        if self.is_subset(universe) {
+            Between::new(self.bits, universe.bits)
+        } else {
+            Between { lower: 0, free: 0, next: None, phantom: PhantomData }
+        }
+    }
+
+    /// Returns the bits of the set as a `u8`, or an error if the set
+    /// contains a variant whose `to_u32` is above 7.
+    pub fn to_bits_u8(&self) -> Result<u8, NarrowBitsError> {
+        if self.bits >> 8 == 0 {
+            Ok(self.bits as u8)
+        } else {
+            Err(NarrowBitsError { bits: self.bits })
+        }
+    }
+
+    /// Returns the bits of the set as a `u16`, or an error if the set
+    /// contains a variant whose `to_u32` is above 15.
+    pub fn to_bits_u16(&self) -> Result<u16, NarrowBitsError> {
+        if self.bits >> 16 == 0 {
+            Ok(self.bits as u16)
+        } else {
+            Err(NarrowBitsError { bits: self.bits })
+        }
+    }
+
+    /// Returns an array where the element at index `i` is `true` if the set
+    /// contains the variant with value `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the set contains a variant with a value of `N` or more.
+    pub fn to_bool_array<const N: usize>(&self) -> [bool; N] {
+        let mut array = [false; N];
+        for element in self {
+            let index = element.to_u32() as usize;
+            assert!(index < N, "EnumSet contains a value out of the array bounds.");
+            array[index] = true;
+        }
+        array
+    }
+}
+
+impl<E: Finite> EnumSet<E> {
+    /// Returns a `Display` representation of the set listing every variant
+    /// with a marker showing whether the set contains it.
+    ///
+    /// Variants are named by their `Debug` representations in
+    /// `Finite::NAME_CASE`.
+    pub fn checklist(&self) -> Checklist<E> {
+        Checklist { bits: self.bits, phantom: PhantomData }
+    }
+
+    /// Returns the set with the given bits, or `None` if a bit is set which
+    /// does not correspond to a variant.
+    pub fn from_bits_u8(bits: u8) -> Option<Self> {
+        Self::from_bits_checked(u32::from(bits))
+    }
+
+    /// Returns the set with the given bits, or `None` if a bit is set which
+    /// does not correspond to a variant.
+    pub fn from_bits_u16(bits: u16) -> Option<Self> {
+        Self::from_bits_checked(u32::from(bits))
+    }
+
+    fn from_bits_checked(bits: u32) -> Option<Self> {
+        if bits & !universe::<E>() == 0 {
+            Some(Self::new_with_bits(bits))
+        } else {
+            None
+        }
+    }
+
+    /// Returns a set containing every variant of `E`.
+    #[inline]
+    pub fn all() -> Self {
+        Self::new_with_bits(universe::<E>())
+    }
+
+    /// Returns the set of variants of `E` which are not in the set.
+    #[inline]
+    pub fn complement(&self) -> Self {
+        Self::new_with_bits(universe::<E>() & !self.bits)
+    }
+
+    /// Returns the result of the operation `op` on the set and `other`.
+    ///
+    /// `SetOp::Complement` ignores `other`.
+    pub fn apply<O: borrow::Borrow<Self>>(&self, op: SetOp, other: O) -> Self {
+        match op {
+            SetOp::Union => self.union(other),
+            SetOp::Intersection => self.intersection(other),
+            SetOp::Difference => self.difference(other),
+            SetOp::SymmetricDifference => self.symmetric_difference(other),
+            SetOp::Complement => self.complement(),
+        }
+    }
+
+    /// Returns an iterator over every possible set of variants of `E`, in
+    /// increasing order of their bits, starting with the empty set.
+    pub fn exhaustive() -> Between<E> {
+        Between::new(0, universe::<E>())
+    }
+
+    /// Parses a set from the string representation of its bit mask in the
+    /// given radix.
+    ///
+    /// A `0b`, `0o` or `0x` prefix is accepted for radix 2, 8 and 16
+    /// respectively.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in the range from 2 to 36.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseRadixError> {
+        let bits = raw::parse_radix(s, radix).map_err(ParseRadixError::InvalidMask)?;
+        if bits & !universe::<E>() != 0 {
+            return Err(ParseRadixError::UnknownVariant);
+        }
+        Ok(Self::new_with_bits(bits))
+    }
+}
+
+/// An operation on sets, applied with `EnumSet::apply`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SetOp {
+    /// The union of both sets, see `EnumSet::union`.
+    Union,
+    /// The intersection of both sets, see `EnumSet::intersection`.
+    Intersection,
+    /// The difference between both sets, see `EnumSet::difference`.
+    Difference,
+    /// The symmetric difference between both sets, see
+    /// `EnumSet::symmetric_difference`.
+    SymmetricDifference,
+    /// The complement of the first set, see `EnumSet::complement`.
+    Complement,
+}
+
+/// An error returned by `EnumSet::to_bits_u8` and `EnumSet::to_bits_u16`
+/// when the set does not fit in the narrower integer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NarrowBitsError {
+    bits: u32,
+}
+
+impl NarrowBitsError {
+    /// Returns the bits of the set which failed to convert.
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+}
+
+impl fmt::Display for NarrowBitsError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "bits {:#x} do not fit in the target integer", self.bits)
+    }
+}
+
+impl error::Error for NarrowBitsError {}
+
+/// An error returned by `EnumSet::from_str_radix`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRadixError {
+    /// The string is not a valid 32-bit mask in the given radix.
+    InvalidMask(num::ParseIntError),
+    /// The mask contains bits which do not correspond to a variant.
+    UnknownVariant,
+}
+
+impl fmt::Display for ParseRadixError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseRadixError::InvalidMask(ref err) => write!(fmt, "invalid mask: {}", err),
+            ParseRadixError::UnknownVariant => fmt.write_str("mask contains an unknown variant"),
+        }
+    }
+}
+
+impl error::Error for ParseRadixError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            ParseRadixError::InvalidMask(ref err) => Some(err),
+            ParseRadixError::UnknownVariant => None,
+        }
+    }
 }
 
 impl<E: CLike> ops::Sub for EnumSet<E> {
     type Output = Self;
 
+    #[inline]
     fn sub(self, other: Self) -> Self {
         self.difference(other)
     }
@@ -183,6 +1066,7 @@ impl<E: CLike> ops::Sub for EnumSet<E> {
 impl<E: CLike> ops::BitOr for EnumSet<E> {
     type Output = Self;
 
+    #[inline]
     fn bitor(self, other: Self) -> Self {
         self.union(other)
     }
@@ -191,6 +1075,7 @@ impl<E: CLike> ops::BitOr for EnumSet<E> {
 impl<E: CLike> ops::BitAnd for EnumSet<E> {
     type Output = Self;
 
+    #[inline]
     fn bitand(self, other: Self) -> Self {
         self.intersection(other)
     }
@@ -199,11 +1084,121 @@ impl<E: CLike> ops::BitAnd for EnumSet<E> {
 impl<E: CLike> ops::BitXor for EnumSet<E> {
     type Output = Self;
 
+    #[inline]
     fn bitxor(self, other: Self) -> Self {
         self.symmetric_difference(other)
     }
 }
 
+macro_rules! impl_ref_binop {
+    ($imp:ident, $method:ident, $set_method:ident) => {
+        impl<E: CLike> ops::$imp<EnumSet<E>> for &EnumSet<E> {
+            type Output = EnumSet<E>;
+
+            #[inline]
+            fn $method(self, other: EnumSet<E>) -> EnumSet<E> {
+                self.$set_method(other)
+            }
+        }
+
+        impl<E: CLike> ops::$imp<&EnumSet<E>> for EnumSet<E> {
+            type Output = EnumSet<E>;
+
+            #[inline]
+            fn $method(self, other: &EnumSet<E>) -> EnumSet<E> {
+                self.$set_method(EnumSet::new_with_bits(other.bits))
+            }
+        }
+
+        impl<E: CLike> ops::$imp<&EnumSet<E>> for &EnumSet<E> {
+            type Output = EnumSet<E>;
+
+            #[inline]
+            fn $method(self, other: &EnumSet<E>) -> EnumSet<E> {
+                self.$set_method(EnumSet::new_with_bits(other.bits))
+            }
+        }
+    }
+}
+
+impl_ref_binop!(Sub, sub, difference);
+impl_ref_binop!(BitOr, bitor, union);
+impl_ref_binop!(BitAnd, bitand, intersection);
+impl_ref_binop!(BitXor, bitxor, symmetric_difference);
+
+impl<E: Finite> ops::Not for EnumSet<E> {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self {
+        self.complement()
+    }
+}
+
+impl<E: Finite> ops::Not for &EnumSet<E> {
+    type Output = EnumSet<E>;
+
+    #[inline]
+    fn not(self) -> EnumSet<E> {
+        self.complement()
+    }
+}
+
+/// A value of a `CLike` enum together with its bit, checked once on
+/// construction.
+///
+/// The `*_member` methods of `EnumSet` take a `Member` rather than a value,
+/// so they compile to a single bit operation without the bound check done by
+/// `insert`, `remove` and `contains`. Constructing the members outside of a
+/// hot loop moves that check out of it.
+pub struct Member<E> {
+    bit: u32,
+    phantom: PhantomData<E>,
+}
+
+impl<E: CLike> Member<E> {
+    /// Returns the member for the given value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `to_u32` returns a value above 31.
+    #[inline]
+    pub fn new(value: E) -> Self {
+        Member { bit: bit(&value), phantom: PhantomData }
+    }
+
+    /// Returns the member for the given value, or `None` if `to_u32`
+    /// returns a value above 31.
+    #[inline]
+    pub fn try_new(value: E) -> Option<Self> {
+        match value.to_u32() {
+            index @ 0..=31 => Some(Member { bit: 1 << index, phantom: PhantomData }),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the member.
+    #[inline]
+    pub fn get(&self) -> E {
+        // Safe because the bit was obtained from `to_u32`.
+        unsafe { E::from_u32(self.bit.trailing_zeros()) }
+    }
+}
+
+impl<E> Clone for Member<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for Member<E> {}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for Member<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_tuple("Member").field(&self.get()).finish()
+    }
+}
+
 #[derive(Clone)]
 /// An iterator over an `EnumSet`.
 pub struct Iter<E> {
@@ -215,15 +1210,15 @@ pub struct Iter<E> {
 impl<E: CLike> Iterator for Iter<E> {
     type Item = E;
 
+    #[inline]
     fn next(&mut self) -> Option<E> {
         if self.bits == 0 {
             return None;
         }
 
-        while (self.bits & 1) == 0 {
-            self.index += 1;
-            self.bits >>= 1;
-        }
+        let skip = self.bits.trailing_zeros();
+        self.index += skip;
+        self.bits >>= skip;
 
         // Safe because of the invariant that only valid bits are set (see
         // comment on the `bit` member of this struct).
@@ -233,6 +1228,7 @@ impl<E: CLike> Iterator for Iter<E> {
         Some(elem)
     }
 
+    #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
         let exact = self.bits.count_ones() as usize;
         (exact, Some(exact))
@@ -241,12 +1237,117 @@ impl<E: CLike> Iterator for Iter<E> {
 
 impl<E: CLike> ExactSizeIterator for Iter<E> {}
 
+/// Formats the remaining elements of the iterator as a list.
+impl<E: CLike + fmt::Debug> fmt::Debug for Iter<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(Iter::<E> { index: self.index, bits: self.bits, phantom: PhantomData }).finish()
+    }
+}
+
+#[derive(Clone)]
+/// An iterator over the sets between a lower and an upper bound.
+///
+/// This is returned by `EnumSet::subsets` and `EnumSet::supersets_within`.
+pub struct Between<E> {
+    lower: u32,
+    // The bits of the upper bound which are not in the lower bound.
+    free: u32,
+    // The bits of the next set which are not in the lower bound.
+    next: Option<u32>,
+    phantom: PhantomData<*mut E>,
+}
+
+impl<E> Between<E> {
+    fn new(lower: u32, upper: u32) -> Self {
+        Between { lower, free: upper & !lower, next: Some(0), phantom: PhantomData }
+    }
+}
+
+impl<E: CLike> Iterator for Between<E> {
+    type Item = EnumSet<E>;
+
+    fn next(&mut self) -> Option<EnumSet<E>> {
+        let current = self.next?;
+        self.next = raw::next_submask(current, self.free);
+        Some(EnumSet::new_with_bits(self.lower | current))
+    }
+}
+
+/// An iterator over the subsets of an `EnumSet` in increasing order of their
+/// lengths.
+///
+/// This is returned by `EnumSet::subsets_by_len`.
+pub struct SubsetsByLen<E> {
+    elements: u32,
+    // The length of the subsets currently being yielded.
+    len: u32,
+    // The next subset, as a mask over the positions of `elements` rather than
+    // over the bits of the set.
+    next: Option<u64>,
+    phantom: PhantomData<*mut E>,
+}
+
+impl<E: CLike> Iterator for SubsetsByLen<E> {
+    type Item = EnumSet<E>;
+
+    fn next(&mut self) -> Option<EnumSet<E>> {
+        let current = self.next?;
+        self.next = raw::next_combination(current, self.elements.count_ones(), &mut self.len);
+        Some(EnumSet::new_with_bits(raw::scatter(current, self.elements)))
+    }
+}
+
+/// An iterator over an `EnumSet` in a caller-defined order.
+///
+/// This is returned by `EnumSet::iter_ordered_by`.
+pub struct OrderedIter<'a, E: 'a> {
+    order: slice::Iter<'a, E>,
+    // The elements which have not been yielded yet.
+    bits: u32,
+}
+
+impl<'a, E: CLike> Iterator for OrderedIter<'a, E> {
+    type Item = E;
+
+    fn next(&mut self) -> Option<E> {
+        for value in &mut self.order {
+            let value = bit(value);
+            if self.bits & value != 0 {
+                self.bits &= !value;
+                // Safe because the bit was obtained from `to_u32`.
+                return Some(unsafe { E::from_u32(value.trailing_zeros()) });
+            }
+        }
+        if self.bits == 0 {
+            return None;
+        }
+        let index = self.bits.trailing_zeros();
+        self.bits &= self.bits - 1;
+        // Safe because of the invariant that only valid bits are set.
+        Some(unsafe { E::from_u32(index) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let exact = self.bits.count_ones() as usize;
+        (exact, Some(exact))
+    }
+}
+
+impl<'a, E: CLike> ExactSizeIterator for OrderedIter<'a, E> {}
+
 impl<E: CLike> Default for EnumSet<E> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl<E: DefaultSet> EnumSet<E> {
+    /// Returns the set of the variants of `E` enabled by default.
+    pub fn default_set() -> Self {
+        E::DEFAULT_SET
+    }
+}
+
 impl<E: CLike> iter::FromIterator<E> for EnumSet<E> {
     fn from_iter<I: IntoIterator<Item = E>>(iterator: I) -> Self {
         let mut ret = Self::new();
@@ -263,22 +1364,304 @@ impl<E: CLike> Extend<E> for EnumSet<E> {
     }
 }
 
-impl<'a, E: CLike> IntoIterator for &'a EnumSet<E> {
+impl<'a, E: CLike + Copy + 'a> iter::FromIterator<&'a E> for EnumSet<E> {
+    fn from_iter<I: IntoIterator<Item = &'a E>>(iterator: I) -> Self {
+        let mut ret = Self::new();
+        ret.extend(iterator);
+        ret
+    }
+}
+
+impl<'a, E: CLike + Copy + 'a> Extend<&'a E> for EnumSet<E> {
+    fn extend<I: IntoIterator<Item = &'a E>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+/// Extending a set with other sets adds all of their elements.
+impl<E: CLike> Extend<EnumSet<E>> for EnumSet<E> {
+    fn extend<I: IntoIterator<Item = EnumSet<E>>>(&mut self, iter: I) {
+        for set in iter {
+            self.bits |= set.bits;
+        }
+    }
+}
+
+impl<'a, E: CLike + 'a> Extend<&'a EnumSet<E>> for EnumSet<E> {
+    fn extend<I: IntoIterator<Item = &'a EnumSet<E>>>(&mut self, iter: I) {
+        for set in iter {
+            self.bits |= set.bits;
+        }
+    }
+}
+
+impl<E: CLike> IntoIterator for &EnumSet<E> {
     type Item = E;
     type IntoIter = Iter<E>;
     fn into_iter(self) -> Iter<E> { self.iter() }
 }
 
+impl<E: CLike> From<EnumSet<E>> for Vec<E> {
+    fn from(set: EnumSet<E>) -> Self {
+        set.iter().collect()
+    }
+}
+
+impl<E: CLike + hash::Hash + Eq> From<EnumSet<E>> for HashSet<E> {
+    fn from(set: EnumSet<E>) -> Self {
+        set.iter().collect()
+    }
+}
+
+impl<E: CLike + Ord> From<EnumSet<E>> for BTreeSet<E> {
+    fn from(set: EnumSet<E>) -> Self {
+        set.iter().collect()
+    }
+}
+
+impl<E: CLike> From<Vec<E>> for EnumSet<E> {
+    fn from(elements: Vec<E>) -> Self {
+        elements.into_iter().collect()
+    }
+}
+
+impl<E: CLike, S> From<HashSet<E, S>> for EnumSet<E> {
+    fn from(elements: HashSet<E, S>) -> Self {
+        elements.into_iter().collect()
+    }
+}
+
+impl<E: CLike> From<BTreeSet<E>> for EnumSet<E> {
+    fn from(elements: BTreeSet<E>) -> Self {
+        elements.into_iter().collect()
+    }
+}
+
+impl<E: Finite, const N: usize> From<[bool; N]> for EnumSet<E> {
+    /// Creates a set containing the variant with value `i` for every `true`
+    /// element at index `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a `true` element has no corresponding variant.
+    fn from(array: [bool; N]) -> Self {
+        let mut bits = 0;
+        for (index, &value) in array.iter().enumerate() {
+            if value {
+                assert!(index < E::VARIANT_COUNT as usize,
+                        "No variant corresponds to index {}.", index);
+                bits |= 1 << index;
+            }
+        }
+        Self::new_with_bits(bits)
+    }
+}
+
+/// A wrapper ordering sets by inclusion.
+///
+/// `ByInclusion(a) < ByInclusion(b)` holds if `a` is a proper subset of `b`.
+/// Sets where neither contains the other are not comparable.
+#[derive(Copy, Clone, Debug)]
+pub struct ByInclusion<T>(pub T);
+
+impl<E: CLike> PartialEq for ByInclusion<EnumSet<E>> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.bits == other.0.bits
+    }
+}
+
+impl<E: CLike> Eq for ByInclusion<EnumSet<E>> {}
+
+impl<E: CLike> PartialOrd for ByInclusion<EnumSet<E>> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.0.subset_cmp(&other.0)
+    }
+}
+
+/// A wrapper ordering sets like the sorted lists of their elements.
+///
+/// Elements are ordered by their `to_u32` values, and the lists are compared
+/// lexicographically, so `{A}` < `{A, B}` < `{B}`. This is a total order,
+/// unlike the order on `EnumSet` itself, which compares the bits as
+/// integers.
+#[derive(Copy, Clone, Debug)]
+pub struct ByElements<T>(pub T);
+
+impl<E: CLike> PartialEq for ByElements<EnumSet<E>> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.bits == other.0.bits
+    }
+}
+
+impl<E: CLike> Eq for ByElements<EnumSet<E>> {}
+
+impl<E: CLike> PartialOrd for ByElements<EnumSet<E>> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E: CLike> Ord for ByElements<EnumSet<E>> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        raw::cmp_elements(self.0.bits, other.0.bits)
+    }
+}
+
+// Used by `set_expr!` to compute the bits of an expression with constant
+// functions, while inferring the element type from the operands.
+#[doc(hidden)]
+pub struct __SetExpr<E>(PhantomData<E>);
+
+#[doc(hidden)]
+impl<E: CLike> __SetExpr<E> {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        __SetExpr(PhantomData)
+    }
+
+    pub const fn variant(&self, _: &E, value: u32) -> u32 {
+        if value >= 32 {
+            panic!("EnumSet only supports up to 31 variants.");
+        }
+        1 << value
+    }
+
+    pub const fn set(&self, set: &EnumSet<E>) -> u32 {
+        set.bits
+    }
+
+    pub const fn finish(&self, bits: u32) -> EnumSet<E> {
+        EnumSet::__from_bits(bits)
+    }
+}
+
+#[doc(hidden)]
+impl<E: Finite> __SetExpr<E> {
+    pub const fn complement(&self, bits: u32) -> u32 {
+        universe::<E>() & !bits
+    }
+}
+
+/// Builds a set from a boolean expression over variants.
+///
+/// Every variant in the expression stands for the set containing only that
+/// variant. The operators `&`, `|`, `^` and `-` are intersection, union,
+/// symmetric difference and difference, and `!` is the complement with
+/// respect to every variant, which requires the element type to implement
+/// `Finite`. Operator precedence follows Rust, so `&` binds tighter than `|`.
+/// A block can be used to embed an arbitrary set expression.
+///
+/// The expression is evaluated with constant arithmetic on the bits of the
+/// set, so it can initialize a `const` or a `static`. Variants are converted
+/// with `as u32`, so their discriminants must be the values returned by
+/// `CLike::to_u32`, as they are for `#[repr(u32)]` enums.
+///
+/// ```
+/// #[macro_use]
+/// extern crate enum_set;
+///
+/// use enum_set::{CLike, EnumSet, Finite};
+/// use std::mem;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u32)]
+/// enum Perm {
+///     Read, Write, Exec
+/// }
+///
+/// impl CLike for Perm {
+///     fn to_u32(&self) -> u32 {
+///         *self as u32
+///     }
+///
+///     unsafe fn from_u32(v: u32) -> Perm {
+///         mem::transmute(v)
+///     }
+/// }
+///
+/// unsafe impl Finite for Perm {
+///     const VARIANT_COUNT: u32 = 3;
+/// }
+///
+/// const DEFAULT: EnumSet<Perm> = set_expr!(Perm::Read & !Perm::Write | Perm::Exec);
+///
+/// fn main() {
+///     assert_eq!(vec![Perm::Read, Perm::Exec], DEFAULT.iter().collect::<Vec<_>>());
+///
+///     let none = EnumSet::new();
+///     let set = set_expr!(!(Perm::Read | { none }));
+///     assert_eq!(vec![Perm::Write, Perm::Exec], set.iter().collect::<Vec<_>>());
+/// }
+/// ```
+#[macro_export]
+macro_rules! set_expr {
+    (@munch $w:ident $nots:tt [$($out:tt)*]) => {
+        $($out)*
+    };
+    (@munch $w:ident $nots:tt $out:tt ( $($inner:tt)* ) $($rest:tt)*) => {
+        $crate::set_expr!(@operand $w $nots $out [
+            ($crate::set_expr!(@munch $w [] [] $($inner)*))
+        ] $($rest)*)
+    };
+    (@munch $w:ident $nots:tt $out:tt { $($inner:tt)* } $($rest:tt)*) => {
+        $crate::set_expr!(@operand $w $nots $out [$w.set(&{ $($inner)* })] $($rest)*)
+    };
+    (@munch $w:ident [$($nots:tt)*] $out:tt ! $($rest:tt)*) => {
+        $crate::set_expr!(@munch $w [! $($nots)*] $out $($rest)*)
+    };
+    (@munch $w:ident $nots:tt [$($out:tt)*] - $($rest:tt)*) => {
+        $crate::set_expr!(@munch $w $nots [$($out)* & !] $($rest)*)
+    };
+    (@munch $w:ident $nots:tt $out:tt $op:tt $($rest:tt)*) => {
+        $crate::set_expr!(@operator $w $nots $out $op $($rest)*)
+    };
+    (@operator $w:ident $nots:tt [$($out:tt)*] & $($rest:tt)*) => {
+        $crate::set_expr!(@munch $w $nots [$($out)* &] $($rest)*)
+    };
+    (@operator $w:ident $nots:tt [$($out:tt)*] | $($rest:tt)*) => {
+        $crate::set_expr!(@munch $w $nots [$($out)* |] $($rest)*)
+    };
+    (@operator $w:ident $nots:tt [$($out:tt)*] ^ $($rest:tt)*) => {
+        $crate::set_expr!(@munch $w $nots [$($out)* ^] $($rest)*)
+    };
+    (@operator $w:ident $nots:tt $out:tt $first:ident $($rest:tt)*) => {
+        $crate::set_expr!(@path $w $nots $out [$first] $($rest)*)
+    };
+    (@path $w:ident $nots:tt $out:tt [$($path:tt)*] :: $segment:ident $($rest:tt)*) => {
+        $crate::set_expr!(@path $w $nots $out [$($path)* :: $segment] $($rest)*)
+    };
+    (@path $w:ident $nots:tt $out:tt [$($path:tt)*] $($rest:tt)*) => {
+        $crate::set_expr!(@operand $w $nots $out [
+            $w.variant(&$($path)*, $($path)* as u32)
+        ] $($rest)*)
+    };
+    // Applies the pending `!`s to a complete operand.
+    (@operand $w:ident [] [$($out:tt)*] [$($operand:tt)*] $($rest:tt)*) => {
+        $crate::set_expr!(@munch $w [] [$($out)* $($operand)*] $($rest)*)
+    };
+    (@operand $w:ident [! $($nots:tt)*] $out:tt [$($operand:tt)*] $($rest:tt)*) => {
+        $crate::set_expr!(@operand $w [$($nots)*] $out [$w.complement($($operand)*)] $($rest)*)
+    };
+    ($($expr:tt)+) => {{
+        let w = $crate::__SetExpr::new();
+        w.finish($crate::set_expr!(@munch w [] [] $($expr)+))
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     use self::Foo::*;
     use std::mem;
 
-    use super::{EnumSet, CLike};
+    use std::cmp::Ordering;
+    use std::collections::{BTreeSet, HashSet};
+    use std::fmt;
 
-    #[derive(Copy, Clone, PartialEq, Debug)]
+    use super::{ByElements, ByInclusion, EnumSet, CLike, Finite, Member, ParseRadixError, SetOp};
+
+    #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
     #[repr(u32)]
-    enum Foo {
+    pub enum Foo {
         A, B, C
     }
 
@@ -292,6 +1675,35 @@ mod tests {
         }
     }
 
+    unsafe impl Finite for Foo {
+        const VARIANT_COUNT: u32 = 3;
+        const ALIASES: &'static [(&'static str, u32)] = &[("Alpha", 0), ("Gamma", 2)];
+    }
+
+    impl fmt::Display for Foo {
+        fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            write!(fmt, "{:?}", self)
+        }
+    }
+
+    pub fn set(elements: &[Foo]) -> EnumSet<Foo> {
+        elements.iter().collect()
+    }
+
+    // Names in kebab case, looked up ignoring case.
+    ::enum_set_flags! {
+        #[derive(Debug, PartialEq)]
+        pub enum Mode {
+            ReadOnly,
+            ReadWrite(alias = "rw"),
+            HTTPProxy,
+        }
+
+        rename_all = KebabCase;
+
+        case_insensitive = true;
+    }
+
     #[test]
     fn test_new() {
         let e: EnumSet<Foo> = EnumSet::new();
@@ -308,6 +1720,58 @@ mod tests {
         assert_eq!("{A, C}", format!("{:?}", e));
     }
 
+    #[test]
+    fn test_format_flags() {
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        assert_eq!("{A, C}    |", format!("{:<10?}|", e));
+        assert_eq!("    {A, C}|", format!("{:>10?}|", e));
+        assert_eq!("**{A, C}**", format!("{:*^10?}", e));
+        assert_eq!("{A, ...}", format!("{:.1?}", e));
+        assert_eq!("{A, C}", format!("{:3?}", e));
+        assert_eq!("A, C", e.to_string());
+        assert_eq!("A, C  |", format!("{:6}|", e));
+        assert_eq!("-A, ...", format!("{:->7.1}", e));
+        assert_eq!("", format!("{:.0}", EnumSet::<Foo>::new()));
+    }
+
+    #[test]
+    fn test_debug_iter() {
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        let mut iter = e.iter();
+        assert_eq!("[A, C]", format!("{:?}", iter));
+        iter.next();
+        assert_eq!("[C]", format!("{:?}", iter));
+    }
+
+    #[test]
+    fn test_checklist() {
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        assert_eq!("[x] A\n[ ] B\n[x] C", e.checklist().to_string());
+        assert_eq!("\u{2713} A\n\u{2717} B\n\u{2713} C", format!("{:#}", e.checklist()));
+    }
+
+    #[test]
+    fn test_debug_indices() {
+        struct NoDebug;
+
+        impl CLike for NoDebug {
+            fn to_u32(&self) -> u32 {
+                5
+            }
+
+            unsafe fn from_u32(_: u32) -> NoDebug {
+                NoDebug
+            }
+        }
+
+        let mut e = EnumSet::new();
+        assert_eq!("EnumSet{}", format!("{:?}", e.debug_indices()));
+        e.insert(NoDebug);
+        assert_eq!("EnumSet{5}", format!("{:?}", e.debug_indices()));
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        assert_eq!("EnumSet{0, 2}", format!("{:?}", e.debug_indices()));
+    }
+
     #[test]
     fn test_len() {
         let mut e = EnumSet::new();
@@ -316,12 +1780,33 @@ mod tests {
         e.insert(B);
         e.insert(C);
         assert_eq!(e.len(), 3);
-        e.remove(&A);
+        e.remove(A);
         assert_eq!(e.len(), 2);
         e.clear();
         assert_eq!(e.len(), 0);
     }
 
+    #[test]
+    fn test_take() {
+        let mut e = EnumSet::new();
+        e.insert(A);
+        e.insert(C);
+        let taken = e.take();
+        assert!(e.is_empty());
+        assert_eq!(vec![A, C], taken.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_replace_with() {
+        let mut e = EnumSet::new();
+        e.insert(A);
+        let mut other = EnumSet::new();
+        other.insert(B);
+        let old = e.replace_with(other);
+        assert_eq!(vec![A], old.iter().collect::<Vec<_>>());
+        assert_eq!(other, e);
+    }
+
     ///////////////////////////////////////////////////////////////////////////
     // intersect
 
@@ -388,19 +1873,87 @@ mod tests {
         assert!(!e2.is_superset(&e3));
     }
 
+    #[test]
+    fn test_subset_cmp() {
+        let mut e1: EnumSet<Foo> = EnumSet::new();
+        e1.insert(A);
+
+        let mut e2: EnumSet<Foo> = EnumSet::new();
+        e2.insert(A);
+        e2.insert(B);
+
+        let mut e3: EnumSet<Foo> = EnumSet::new();
+        e3.insert(C);
+
+        assert_eq!(Some(Ordering::Less), e1.subset_cmp(&e2));
+        assert_eq!(Some(Ordering::Greater), e2.subset_cmp(&e1));
+        assert_eq!(Some(Ordering::Equal), e2.subset_cmp(&e2));
+        assert_eq!(None, e2.subset_cmp(&e3));
+
+        assert!(ByInclusion(e1) < ByInclusion(e2));
+        assert!(ByInclusion(e2) >= ByInclusion(e1));
+        assert_eq!(None, ByInclusion(e1).partial_cmp(&ByInclusion(e3)));
+    }
+
     #[test]
     fn test_contains() {
+        let mut e1: EnumSet<Foo> = EnumSet::new();
+        e1.insert(A);
+        assert!(e1.contains(A));
+        assert!(!e1.contains(B));
+        assert!(!e1.contains(C));
+
+        e1.insert(A);
+        e1.insert(B);
+        assert!(e1.contains(A));
+        assert!(e1.contains(B));
+        assert!(!e1.contains(C));
+    }
+
+    #[test]
+    #[allow(clippy::needless_borrows_for_generic_args)]
+    fn test_contains_and_remove_by_reference() {
         let mut e1: EnumSet<Foo> = EnumSet::new();
         e1.insert(A);
         assert!(e1.contains(&A));
         assert!(!e1.contains(&B));
-        assert!(!e1.contains(&C));
+        assert!(e1.remove(&A));
+        assert!(!e1.remove(&A));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let base: EnumSet<Foo> = [A, B].iter().collect();
+        let allow: EnumSet<Foo> = [C].iter().collect();
+        let deny: EnumSet<Foo> = [A, C].iter().collect();
+
+        let expected: EnumSet<Foo> = [B].iter().collect();
+        assert_eq!(expected, EnumSet::resolve(allow, deny, base));
+        assert_eq!(base | allow, EnumSet::resolve(allow, EnumSet::new(), base));
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    // similarity
 
+    #[test]
+    fn test_similarity_metrics() {
+        let mut e1: EnumSet<Foo> = EnumSet::new();
         e1.insert(A);
         e1.insert(B);
-        assert!(e1.contains(&A));
-        assert!(e1.contains(&B));
-        assert!(!e1.contains(&C));
+
+        let mut e2: EnumSet<Foo> = EnumSet::new();
+        e2.insert(B);
+        e2.insert(C);
+
+        assert_eq!(1, e1.overlap_len(&e2));
+        assert_eq!(2, e1.hamming_distance(&e2));
+        assert_eq!(1.0 / 3.0, e1.jaccard_index(&e2));
+        assert_eq!(1.0, e1.jaccard_index(&e1));
+        assert_eq!(0, e1.hamming_distance(&e1));
+
+        let empty: EnumSet<Foo> = EnumSet::new();
+        assert_eq!(1.0, empty.jaccard_index(&empty));
+        assert_eq!(0.0, empty.jaccard_index(&e1));
     }
 
     ///////////////////////////////////////////////////////////////////////////
@@ -430,6 +1983,143 @@ mod tests {
         assert_eq!(vec![A,B,C], elems);
     }
 
+    #[test]
+    fn test_from_iterator_of_references() {
+        let elems = [A, C];
+        let e: EnumSet<Foo> = elems.iter().collect();
+        assert_eq!(vec![A,C], e.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_extend_from_references() {
+        let mut e: EnumSet<Foo> = EnumSet::new();
+        e.insert(A);
+        e.extend(&[B, C]);
+        assert_eq!(vec![A,B,C], e.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_operation_iterators() {
+        let mut e1: EnumSet<Foo> = EnumSet::new();
+        e1.insert(A);
+        e1.insert(B);
+        let mut e2: EnumSet<Foo> = EnumSet::new();
+        e2.insert(B);
+        e2.insert(C);
+
+        assert_eq!(vec![A,B,C], e1.union_iter(&e2).collect::<Vec<_>>());
+        assert_eq!(vec![B], e1.intersection_iter(&e2).collect::<Vec<_>>());
+        assert_eq!(vec![A], e1.difference_iter(&e2).collect::<Vec<_>>());
+        assert_eq!(vec![A,C], e1.symmetric_difference_iter(&e2).collect::<Vec<_>>());
+        assert_eq!(2, e1.symmetric_difference_iter(&e2).len());
+    }
+
+    #[test]
+    fn test_extend_from_sets() {
+        let mut e1: EnumSet<Foo> = EnumSet::new();
+        e1.insert(A);
+        let mut e2: EnumSet<Foo> = EnumSet::new();
+        e2.insert(C);
+
+        let mut e: EnumSet<Foo> = EnumSet::new();
+        e.extend(vec![e1, e2]);
+        assert_eq!(vec![A,C], e.iter().collect::<Vec<_>>());
+
+        let mut e: EnumSet<Foo> = EnumSet::new();
+        e.extend(&[e2, EnumSet::new()]);
+        assert_eq!(vec![C], e.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_std_collection_conversions() {
+        let mut e: EnumSet<Foo> = EnumSet::new();
+        e.insert(A);
+        e.insert(C);
+
+        let vec: Vec<Foo> = e.into();
+        assert_eq!(vec![A, C], vec);
+        assert_eq!(e, EnumSet::from(vec));
+
+        let hash_set: HashSet<Foo> = e.into();
+        assert_eq!(2, hash_set.len());
+        assert!(hash_set.contains(&A) && hash_set.contains(&C));
+        assert_eq!(e, EnumSet::from(hash_set));
+
+        let btree_set: BTreeSet<Foo> = e.into();
+        assert_eq!(vec![A, C], btree_set.iter().cloned().collect::<Vec<_>>());
+        assert_eq!(e, EnumSet::from(btree_set));
+    }
+
+    #[test]
+    fn test_bool_array_conversions() {
+        let mut e: EnumSet<Foo> = EnumSet::new();
+        e.insert(A);
+        e.insert(C);
+
+        assert_eq!([true, false, true], e.to_bool_array::<3>());
+        assert_eq!([true, false, true, false], e.to_bool_array::<4>());
+        assert_eq!(e, EnumSet::from([true, false, true]));
+        assert_eq!(e, EnumSet::from([true, false, true, false]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_to_bool_array_too_short() {
+        let mut e: EnumSet<Foo> = EnumSet::new();
+        e.insert(C);
+        e.to_bool_array::<2>();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_bool_array_unknown_variant() {
+        let _: EnumSet<Foo> = EnumSet::from([false, false, false, true]);
+    }
+
+    #[test]
+    fn test_subsets() {
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        let subsets: Vec<Vec<Foo>> = e.subsets().map(|s| s.iter().collect()).collect();
+        assert_eq!(vec![vec![], vec![A], vec![C], vec![A, C]], subsets);
+        assert_eq!(1, EnumSet::<Foo>::new().subsets().count());
+    }
+
+    #[test]
+    fn test_subsets_by_len() {
+        let e: EnumSet<Foo> = [A, B, C].iter().collect();
+        let subsets: Vec<Vec<Foo>> = e.subsets_by_len().map(|s| s.iter().collect()).collect();
+        assert_eq!(vec![vec![], vec![A], vec![B], vec![C],
+                        vec![A, B], vec![A, C], vec![B, C], vec![A, B, C]], subsets);
+
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        let subsets: Vec<Vec<Foo>> = e.subsets_by_len().map(|s| s.iter().collect()).collect();
+        assert_eq!(vec![vec![], vec![A], vec![C], vec![A, C]], subsets);
+        assert_eq!(1, EnumSet::<Foo>::new().subsets_by_len().count());
+    }
+
+    #[test]
+    fn test_supersets_within() {
+        let e: EnumSet<Foo> = [B].iter().collect();
+        let universe: EnumSet<Foo> = [A, B, C].iter().collect();
+        let supersets: Vec<Vec<Foo>> = e.supersets_within(&universe)
+                                        .map(|s| s.iter().collect())
+                                        .collect();
+        assert_eq!(vec![vec![B], vec![A, B], vec![B, C], vec![A, B, C]], supersets);
+
+        let not_within: EnumSet<Foo> = [A].iter().collect();
+        assert_eq!(0, universe.supersets_within(&not_within).count());
+        assert_eq!(1, universe.supersets_within(&universe).count());
+    }
+
+    #[test]
+    fn test_exhaustive() {
+        let sets: Vec<EnumSet<Foo>> = EnumSet::exhaustive().collect();
+        assert_eq!(8, sets.len());
+        assert!(sets[0].is_empty());
+        assert_eq!(vec![A, B, C], sets[7].iter().collect::<Vec<_>>());
+        assert!(sets.windows(2).all(|pair| pair[0].bits < pair[1].bits));
+    }
+
     #[test]
     fn test_clone_iterator() {
         let mut e: EnumSet<Foo> = EnumSet::new();
@@ -449,6 +2139,41 @@ mod tests {
         assert_eq!(vec![B, C], elems2);
     }
 
+    ///////////////////////////////////////////////////////////////////////////
+    // parsing
+
+    #[test]
+    fn test_from_str_radix() {
+        let mut e: EnumSet<Foo> = EnumSet::new();
+        e.insert(A);
+        e.insert(C);
+
+        assert_eq!(Ok(e), EnumSet::from_str_radix("101", 2));
+        assert_eq!(Ok(e), EnumSet::from_str_radix("0b101", 2));
+        assert_eq!(Ok(e), EnumSet::from_str_radix("5", 10));
+        assert_eq!(Ok(e), EnumSet::from_str_radix("0x5", 16));
+        assert_eq!(Ok(e), EnumSet::from_str_radix("0X05", 16));
+        assert_eq!(Ok(EnumSet::new()), EnumSet::<Foo>::from_str_radix("0", 16));
+    }
+
+    #[test]
+    fn test_from_str_radix_errors() {
+        assert_eq!(Err(ParseRadixError::UnknownVariant),
+                   EnumSet::<Foo>::from_str_radix("8", 16));
+        match EnumSet::<Foo>::from_str_radix("0x", 16) {
+            Err(ParseRadixError::InvalidMask(_)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        match EnumSet::<Foo>::from_str_radix("0\u{e9}", 16) {
+            Err(ParseRadixError::InvalidMask(_)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        match EnumSet::<Foo>::from_str_radix("12", 2) {
+            Err(ParseRadixError::InvalidMask(_)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
     ///////////////////////////////////////////////////////////////////////////
     // operators
 
@@ -495,6 +2220,256 @@ mod tests {
         assert_eq!(vec![A,B], elems);
     }
 
+    #[test]
+    #[allow(clippy::needless_borrows_for_generic_args)]
+    fn test_operation_methods() {
+        let e1: EnumSet<Foo> = [A, C].iter().collect();
+        let e2: EnumSet<Foo> = [B, C].iter().collect();
+
+        assert_eq!(e1 | e2, e1.union(e2));
+        assert_eq!(e1 | e2, e1.union(&e2));
+        assert_eq!(e1 & e2, e1.intersection(&e2));
+        assert_eq!(e1 - e2, e1.difference(&e2));
+        assert_eq!(e1 ^ e2, e1.symmetric_difference(&e2));
+        assert_eq!(e1, e1.union(EnumSet::new()));
+    }
+
+    #[test]
+    fn test_complement() {
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        assert_eq!(vec![B], e.complement().iter().collect::<Vec<_>>());
+        assert_eq!(vec![B], (!e).iter().collect::<Vec<_>>());
+        assert_eq!(e, !!e);
+        assert_eq!(EnumSet::all(), !EnumSet::<Foo>::new());
+        assert_eq!(3, EnumSet::<Foo>::all().len());
+    }
+
+    #[test]
+    fn test_apply() {
+        let e1: EnumSet<Foo> = [A, B].iter().collect();
+        let e2: EnumSet<Foo> = [B, C].iter().collect();
+        assert_eq!(e1 | e2, e1.apply(SetOp::Union, e2));
+        assert_eq!(e1 & e2, e1.apply(SetOp::Intersection, e2));
+        assert_eq!(e1 - e2, e1.apply(SetOp::Difference, e2));
+        assert_eq!(e1 ^ e2, e1.apply(SetOp::SymmetricDifference, e2));
+        assert_eq!(!e1, e1.apply(SetOp::Complement, e2));
+
+        let ops = [(SetOp::Union, e2), (SetOp::Complement, e2)];
+        let result = ops.iter().fold(e1, |acc, &(op, other)| acc.apply(op, other));
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_set_expr() {
+        let e = set_expr!(A & !B | C);
+        assert_eq!(vec![A, C], e.iter().collect::<Vec<_>>());
+        let e = set_expr!(!(Foo::A | Foo::B) ^ C);
+        assert!(e.is_empty());
+        let e: EnumSet<Foo> = set_expr!(!B - { e });
+        assert_eq!(vec![A, C], e.iter().collect::<Vec<_>>());
+        let e = set_expr!(A | B - !C);
+        assert_eq!(vec![A], e.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_set_expr_const() {
+        const READ_ONLY: EnumSet<Foo> = set_expr!(A | B);
+        const EXPR: EnumSet<Foo> = set_expr!(!(A - { READ_ONLY }) & !!C);
+        assert_eq!(vec![A, B], READ_ONLY.iter().collect::<Vec<_>>());
+        assert_eq!(vec![C], EXPR.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[allow(clippy::op_ref)]
+    fn test_operators_on_references() {
+        let mut e1: EnumSet<Foo> = EnumSet::new();
+        e1.insert(A);
+        e1.insert(C);
+
+        let mut e2: EnumSet<Foo> = EnumSet::new();
+        e2.insert(B);
+        e2.insert(C);
+
+        assert_eq!(e1 | e2, &e1 | &e2);
+        assert_eq!(e1 & e2, &e1 & e2);
+        assert_eq!(e1 - e2, e1 - &e2);
+        assert_eq!(e1 ^ e2, &e1 ^ &e2);
+    }
+
+    #[test]
+    fn test_iter_ordered_by() {
+        let e: EnumSet<Foo> = [A, B, C].iter().collect();
+        let ordered: Vec<Foo> = e.iter_ordered_by(&[C, A, B]).collect();
+        assert_eq!(vec![C, A, B], ordered);
+
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        let ordered: Vec<Foo> = e.iter_ordered_by(&[B, C, C]).collect();
+        assert_eq!(vec![C, A], ordered);
+        assert_eq!(2, e.iter_ordered_by(&[]).len());
+    }
+
+    #[test]
+    fn test_score() {
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        assert_eq!(-2, e.score(&[3, 10, -5]));
+        assert_eq!(0, EnumSet::<Foo>::new().score(&[]));
+        assert_eq!(1.5, e.score_f64(&[1.0, 10.0, 0.5]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_score_missing_weight() {
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        e.score(&[1, 2]);
+    }
+
+    #[test]
+    #[allow(clippy::needless_borrows_for_generic_args)]
+    fn test_contains_all_any() {
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        assert!(e.contains_all(&[A, C]));
+        assert!(!e.contains_all([A, B]));
+        assert!(e.contains_all(&[]));
+        assert!(e.contains_any(&[B, C]));
+        assert!(!e.contains_any(vec![B]));
+        assert!(!e.contains_any(&[]));
+        assert!(e.contains_all([A, B, C].iter().filter(|&&value| value != B)));
+    }
+
+    #[test]
+    fn test_next_after_prev_before() {
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        assert_eq!(Some(C), e.next_after(A));
+        assert_eq!(Some(C), e.next_after(B));
+        assert_eq!(Some(A), e.next_after(C));
+        assert_eq!(Some(C), e.prev_before(A));
+        assert_eq!(Some(A), e.prev_before(B));
+        assert_eq!(Some(A), e.prev_before(C));
+
+        let e: EnumSet<Foo> = [B].iter().collect();
+        assert_eq!(Some(B), e.next_after(B));
+        assert_eq!(Some(B), e.prev_before(B));
+        assert_eq!(None, EnumSet::<Foo>::new().next_after(A));
+        assert_eq!(None, EnumSet::<Foo>::new().prev_before(A));
+    }
+
+    #[test]
+    fn test_narrow_bits() {
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        assert_eq!(Ok(0b101), e.to_bits_u8());
+        assert_eq!(Ok(0b101), e.to_bits_u16());
+        assert_eq!(Some(e), EnumSet::from_bits_u8(0b101));
+        assert_eq!(Some(e), EnumSet::from_bits_u16(0b101));
+        assert_eq!(None, EnumSet::<Foo>::from_bits_u8(0b1000));
+        assert_eq!(None, EnumSet::<Foo>::from_bits_u16(0x100));
+
+        let wide: EnumSet<Foo> = EnumSet::new_with_bits(0x101);
+        assert_eq!(0x101, wide.to_bits_u8().unwrap_err().bits());
+        assert_eq!(Ok(0x101), wide.to_bits_u16());
+        let wider: EnumSet<Foo> = EnumSet::new_with_bits(0x10000);
+        assert!(wider.to_bits_u16().is_err());
+    }
+
+    #[test]
+    fn test_by_elements() {
+        let mut sets: Vec<EnumSet<Foo>> = vec![
+            [B].iter().collect(),
+            [A, C].iter().collect(),
+            [C].iter().collect(),
+            [A].iter().collect(),
+            EnumSet::new(),
+            [A, B, C].iter().collect(),
+            [B, C].iter().collect(),
+            [A, B].iter().collect(),
+        ];
+        sets.sort_by_key(|&set| ByElements(set));
+        let lists: Vec<Vec<Foo>> = sets.iter().map(|set| set.iter().collect()).collect();
+        assert_eq!(vec![vec![], vec![A], vec![A, B], vec![A, B, C], vec![A, C],
+                        vec![B], vec![B, C], vec![C]], lists);
+        assert_eq!(ByElements(sets[3]), ByElements(sets[3]));
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut e: EnumSet<Foo> = [A].iter().collect();
+        e.swap(A, C);
+        assert_eq!(e, [C].iter().collect());
+        e.swap(B, A);
+        assert_eq!(e, [C].iter().collect());
+        e.insert(A);
+        e.swap(A, C);
+        assert_eq!(e, [A, C].iter().collect());
+    }
+
+    #[test]
+    fn test_permute() {
+        let mut e: EnumSet<Foo> = [A, B].iter().collect();
+        e.permute(&[B, C, A]);
+        assert_eq!(e, [B, C].iter().collect());
+        e.permute(&[A, A, A]);
+        assert_eq!(e, [A].iter().collect());
+    }
+
+    #[test]
+    fn test_flat_map() {
+        let roles: EnumSet<Foo> = [A, C].iter().collect();
+        let expanded = roles.flat_map(|role| match role {
+            A => [A, B].iter().collect(),
+            B => EnumSet::new(),
+            C => [C].iter().collect(),
+        });
+        assert_eq!(expanded, [A, B, C].iter().collect());
+        assert!(EnumSet::<Foo>::new().flat_map(|_| EnumSet::<Foo>::all()).is_empty());
+    }
+
+    #[test]
+    fn test_find() {
+        let e: EnumSet<Foo> = [A, C].iter().collect();
+        assert_eq!(Some(A), e.find(|_| true));
+        assert_eq!(Some(C), e.find(|value| value != A));
+        assert_eq!(None, e.find(|value| value == B));
+        assert!(e.any_match(|value| value == C));
+        assert!(!e.any_match(|value| value == B));
+        assert!(e.all_match(|value| value != B));
+        assert!(!e.all_match(|value| value == A));
+        assert!(EnumSet::<Foo>::new().all_match(|_| false));
+        assert!(!EnumSet::<Foo>::new().any_match(|_| true));
+    }
+
+    #[test]
+    fn test_closure() {
+        let deps: [EnumSet<Foo>; 3] = [
+            [B].iter().collect(),
+            [C].iter().collect(),
+            EnumSet::new(),
+        ];
+        let a: EnumSet<Foo> = [A].iter().collect();
+        assert_eq!(EnumSet::all(), a.closure(&deps));
+        let c: EnumSet<Foo> = [C].iter().collect();
+        assert_eq!(c, c.closure(&deps));
+        assert_eq!(a, a.closure(&[]));
+
+        let cycle: [EnumSet<Foo>; 2] = [[B].iter().collect(), [A].iter().collect()];
+        let b: EnumSet<Foo> = [B].iter().collect();
+        assert_eq!([A, B].iter().collect::<EnumSet<_>>(), b.closure(&cycle));
+    }
+
+    #[test]
+    fn test_members() {
+        let (a, c) = (Member::new(A), Member::new(C));
+        let mut e = EnumSet::new();
+        assert!(e.insert_member(a));
+        assert!(!e.insert_member(a));
+        assert!(e.contains_member(a));
+        assert!(!e.contains_member(c));
+        assert_eq!(e, [A].iter().collect());
+        assert!(e.remove_member(a));
+        assert!(!e.remove_member(a));
+        assert!(e.is_empty());
+        assert_eq!(C, c.get());
+        assert_eq!("Member(C)", format!("{:?}", c));
+    }
+
     #[test]
     #[should_panic]
     fn test_overflow() {
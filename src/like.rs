@@ -0,0 +1,178 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::borrow;
+
+use crdt::{ORSet, TwoPhaseSet};
+use {CLike, EnumSet, Finite, OpenEnumSet, WatchedEnumSet};
+
+/// The operations common to the set types of this crate.
+///
+/// This allows writing code once for `EnumSet` as well as for the sets
+/// which store or track their elements differently, such as
+/// `WatchedEnumSet`, `OpenEnumSet` and the replicated sets of `crdt`.
+///
+/// ```
+/// use enum_set::{CLike, EnumSet, EnumSetLike};
+///
+/// fn enable_all<E: CLike + Copy, S: EnumSetLike<E>>(set: &mut S, values: &[E]) -> usize {
+///     values.iter().filter(|&&value| set.insert(value)).count()
+/// }
+/// # fn main() {}
+/// ```
+pub trait EnumSetLike<E: CLike> {
+    /// Returns `true` if the set contains the given value.
+    fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool;
+
+    /// Adds the given value to the set.
+    ///
+    /// Returns `true` if the value was not already present in the set.
+    fn insert(&mut self, value: E) -> bool;
+
+    /// Removes a value from the set.
+    ///
+    /// Returns `true` if the value was present in the set.
+    fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool;
+
+    /// Returns the elements of the set as an `EnumSet`.
+    fn to_enum_set(&self) -> EnumSet<E>;
+
+    /// Returns the number of elements in the set.
+    fn len(&self) -> usize {
+        self.to_enum_set().len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    fn is_empty(&self) -> bool {
+        self.to_enum_set().is_empty()
+    }
+}
+
+impl<E: CLike> EnumSetLike<E> for EnumSet<E> {
+    fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        EnumSet::contains(self, value)
+    }
+
+    fn insert(&mut self, value: E) -> bool {
+        EnumSet::insert(self, value)
+    }
+
+    fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        EnumSet::remove(self, value)
+    }
+
+    fn to_enum_set(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.bits)
+    }
+}
+
+impl<E: CLike, F: FnMut(EnumSet<E>, EnumSet<E>)> EnumSetLike<E> for WatchedEnumSet<E, F> {
+    fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        self.get().contains(value)
+    }
+
+    fn insert(&mut self, value: E) -> bool {
+        WatchedEnumSet::insert(self, value)
+    }
+
+    fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        WatchedEnumSet::remove(self, value)
+    }
+
+    fn to_enum_set(&self) -> EnumSet<E> {
+        self.get()
+    }
+}
+
+/// Operates on the known elements, leaving the unknown bits untouched.
+impl<E: Finite> EnumSetLike<E> for OpenEnumSet<E> {
+    fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        OpenEnumSet::contains(self, value)
+    }
+
+    fn insert(&mut self, value: E) -> bool {
+        OpenEnumSet::insert(self, value)
+    }
+
+    fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        OpenEnumSet::remove(self, value)
+    }
+
+    fn to_enum_set(&self) -> EnumSet<E> {
+        self.known()
+    }
+}
+
+impl<E: CLike> EnumSetLike<E> for TwoPhaseSet<E> {
+    fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        TwoPhaseSet::contains(self, value)
+    }
+
+    fn insert(&mut self, value: E) -> bool {
+        TwoPhaseSet::insert(self, value)
+    }
+
+    fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        TwoPhaseSet::remove(self, value)
+    }
+
+    fn to_enum_set(&self) -> EnumSet<E> {
+        self.value()
+    }
+}
+
+impl<E: CLike> EnumSetLike<E> for ORSet<E> {
+    fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        ORSet::contains(self, value)
+    }
+
+    fn insert(&mut self, value: E) -> bool {
+        ORSet::insert(self, value)
+    }
+
+    fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        ORSet::remove(self, value)
+    }
+
+    fn to_enum_set(&self) -> EnumSet<E> {
+        self.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crdt::{ORSet, TwoPhaseSet};
+    use tests::Foo;
+    use tests::Foo::*;
+
+    use {EnumSet, OpenEnumSet, WatchedEnumSet};
+    use super::EnumSetLike;
+
+    fn exercise<S: EnumSetLike<Foo>>(mut set: S) {
+        assert!(set.is_empty());
+        assert!(set.insert(A));
+        assert!(!set.insert(A));
+        assert!(set.insert(C));
+        assert!(set.contains(C));
+        assert!(set.remove(A));
+        assert!(!set.contains(A));
+        assert_eq!(1, set.len());
+        assert_eq!(set.to_enum_set(), [C].iter().collect());
+    }
+
+    #[test]
+    fn test_backends() {
+        exercise(EnumSet::new());
+        exercise(WatchedEnumSet::new(EnumSet::new(), |_, _| {}));
+        exercise(OpenEnumSet::new());
+        exercise(TwoPhaseSet::new());
+        exercise(ORSet::new(1));
+    }
+}
@@ -0,0 +1,271 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lookup of variants by name.
+//!
+//! The name of a variant is its `Debug` representation, which for a derived
+//! implementation is the identifier of the variant, converted to
+//! `Finite::NAME_CASE`. Names are compared ignoring case if
+//! `Finite::CASE_INSENSITIVE_NAMES` is set.
+
+use std::fmt;
+
+use Finite;
+#[cfg(any(feature = "serde", feature = "arrow"))]
+use EnumSet;
+
+/// A convention for the case of variant names, applied to the identifiers
+/// of the variants by `NameCase::apply`.
+///
+/// Words are separated at underscores and at changes from lowercase to
+/// uppercase, keeping runs of uppercase letters together, so `HTTPServer`
+/// and `http_server` both consist of the words `http` and `server`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NameCase {
+    /// The identifier as it is, such as `HttpServer`.
+    Unchanged,
+    /// Every letter in lowercase, such as `httpserver`.
+    Lowercase,
+    /// Every letter in uppercase, such as `HTTPSERVER`.
+    Uppercase,
+    /// Lowercase words separated by underscores, such as `http_server`.
+    SnakeCase,
+    /// Uppercase words separated by underscores, such as `HTTP_SERVER`.
+    ScreamingSnakeCase,
+    /// Lowercase words separated by hyphens, such as `http-server`.
+    KebabCase,
+    /// Uppercase words separated by hyphens, such as `HTTP-SERVER`.
+    ScreamingKebabCase,
+    /// Capitalized words after a lowercase first word, such as `httpServer`.
+    CamelCase,
+    /// Capitalized words, such as `HttpServer`.
+    PascalCase,
+}
+
+impl NameCase {
+    /// Converts the identifier `name` to this case.
+    pub fn apply(self, name: &str) -> String {
+        let (lower, separator) = match self {
+            NameCase::Unchanged => return name.to_owned(),
+            NameCase::Lowercase => return name.to_lowercase(),
+            NameCase::Uppercase => return name.to_uppercase(),
+            NameCase::SnakeCase => (true, "_"),
+            NameCase::ScreamingSnakeCase => (false, "_"),
+            NameCase::KebabCase => (true, "-"),
+            NameCase::ScreamingKebabCase => (false, "-"),
+            NameCase::CamelCase | NameCase::PascalCase => {
+                let mut out = String::with_capacity(name.len());
+                for (index, word) in words(name).enumerate() {
+                    let mut chars = word.chars();
+                    if let Some(first) = chars.next() {
+                        if index == 0 && self == NameCase::CamelCase {
+                            out.extend(first.to_lowercase());
+                        } else {
+                            out.extend(first.to_uppercase());
+                        }
+                    }
+                    out.extend(chars.flat_map(char::to_lowercase));
+                }
+                return out;
+            }
+        };
+        let words = words(name).map(|word| {
+            if lower { word.to_lowercase() } else { word.to_uppercase() }
+        });
+        words.collect::<Vec<_>>().join(separator)
+    }
+}
+
+/// Returns the words of the identifier `name`.
+fn words(name: &str) -> impl Iterator<Item = &str> {
+    name.split('_').filter(|part| !part.is_empty()).flat_map(|part| {
+        let chars: Vec<(usize, char)> = part.char_indices().collect();
+        let mut starts = vec![0];
+        for i in 1..chars.len() {
+            let (start, c) = chars[i];
+            let previous = chars[i - 1].1;
+            let next = chars.get(i + 1).map(|&(_, next)| next);
+            if c.is_uppercase() &&
+               (previous.is_lowercase() || previous.is_numeric() ||
+                (previous.is_uppercase() && next.is_some_and(char::is_lowercase))) {
+                starts.push(start);
+            }
+        }
+        starts.push(part.len());
+        (1..starts.len()).map(move |i| &part[starts[i - 1]..starts[i]])
+    })
+}
+
+/// Returns the name of `variant`.
+pub fn name<E: Finite + fmt::Debug>(variant: &E) -> String {
+    E::NAME_CASE.apply(&format!("{:?}", variant))
+}
+
+/// Returns every variant of `E` along with its name.
+pub fn variants<E: Finite + fmt::Debug>() -> impl Iterator<Item = (E, String)> {
+    (0..E::VARIANT_COUNT).map(|index| {
+        // Safe because every value below `VARIANT_COUNT` is a variant.
+        let variant: E = unsafe { E::from_u32(index) };
+        let name = name(&variant);
+        (variant, name)
+    })
+}
+
+/// Returns whether `a` and `b` are the same name of a variant of `E`.
+fn same_name<E: Finite>(a: &str, b: &str) -> bool {
+    if E::CASE_INSENSITIVE_NAMES {
+        a.chars().flat_map(char::to_lowercase).eq(b.chars().flat_map(char::to_lowercase))
+    } else {
+        a == b
+    }
+}
+
+/// Returns the variant of `E` called `name`, or having `name` among the
+/// aliases in `Finite::ALIASES`.
+pub fn from_name<E: Finite + fmt::Debug>(name: &str) -> Option<E> {
+    variants::<E>().find(|(_, variant_name)| same_name::<E>(variant_name, name))
+                   .map(|(variant, _)| variant)
+                   .or_else(|| {
+                       E::ALIASES.iter()
+                                 .find(|&&(alias, value)| {
+                                     same_name::<E>(alias, name) && value < E::VARIANT_COUNT
+                                 })
+                                 // Safe because every value below `VARIANT_COUNT` is a variant.
+                                 .map(|&(_, value)| unsafe { E::from_u32(value) })
+                   })
+}
+
+/// Returns the set of the variants named by `names`.
+///
+/// `unknown` is called with every name which is not a variant. It returns
+/// the error to stop with, or `Ok(())` to skip the name.
+#[cfg(any(feature = "serde", feature = "arrow"))]
+pub fn collect<E, N, Err, I, F>(names: I, mut unknown: F) -> Result<EnumSet<E>, Err>
+    where E: Finite + fmt::Debug,
+          N: AsRef<str>,
+          I: IntoIterator<Item = Result<N, Err>>,
+          F: FnMut(N) -> Result<(), Err>
+{
+    let mut set = EnumSet::new();
+    for name in names {
+        let name = name?;
+        match from_name::<E>(name.as_ref()) {
+            Some(variant) => {
+                set.insert(variant);
+            }
+            None => unknown(name)?,
+        }
+    }
+    Ok(set)
+}
+
+/// Returns the number of single character insertions, deletions and
+/// substitutions needed to turn `a` into `b`, ignoring case.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().flat_map(char::to_lowercase).collect();
+    let mut row: Vec<usize> = (0..b.len() + 1).collect();
+    for (i, a) in a.chars().flat_map(char::to_lowercase).enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = diagonal + if a == b { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Returns the name of the variant of `E` closest to `name`, if it is close
+/// enough to be a likely typo.
+pub fn suggest<E: Finite + fmt::Debug>(name: &str) -> Option<String> {
+    let limit = (name.chars().count() / 3).max(1);
+    variants::<E>().map(|(_, variant_name)| (edit_distance(name, &variant_name), variant_name))
+                   .filter(|&(distance, _)| distance <= limit)
+                   .min_by_key(|&(distance, _)| distance)
+                   .map(|(_, variant_name)| variant_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::{Foo, Mode};
+
+    use super::{edit_distance, from_name, suggest, NameCase};
+
+    #[test]
+    fn test_name_case() {
+        let convert = |case: NameCase| -> Vec<String> {
+            let names = ["ReadOnly", "HTTPProxy", "read_only", "Version2Beta", "A"];
+            names.iter().map(|name| case.apply(name)).collect()
+        };
+        assert_eq!(vec!["ReadOnly", "HTTPProxy", "read_only", "Version2Beta", "A"],
+                   convert(NameCase::Unchanged));
+        assert_eq!(vec!["readonly", "httpproxy", "read_only", "version2beta", "a"],
+                   convert(NameCase::Lowercase));
+        assert_eq!(vec!["READONLY", "HTTPPROXY", "READ_ONLY", "VERSION2BETA", "A"],
+                   convert(NameCase::Uppercase));
+        assert_eq!(vec!["read_only", "http_proxy", "read_only", "version2_beta", "a"],
+                   convert(NameCase::SnakeCase));
+        assert_eq!(vec!["READ_ONLY", "HTTP_PROXY", "READ_ONLY", "VERSION2_BETA", "A"],
+                   convert(NameCase::ScreamingSnakeCase));
+        assert_eq!(vec!["read-only", "http-proxy", "read-only", "version2-beta", "a"],
+                   convert(NameCase::KebabCase));
+        assert_eq!(vec!["READ-ONLY", "HTTP-PROXY", "READ-ONLY", "VERSION2-BETA", "A"],
+                   convert(NameCase::ScreamingKebabCase));
+        assert_eq!(vec!["readOnly", "httpProxy", "readOnly", "version2Beta", "a"],
+                   convert(NameCase::CamelCase));
+        assert_eq!(vec!["ReadOnly", "HttpProxy", "ReadOnly", "Version2Beta", "A"],
+                   convert(NameCase::PascalCase));
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Some(Foo::B), from_name::<Foo>("B"));
+        assert_eq!(Some(Foo::A), from_name::<Foo>("Alpha"));
+        assert_eq!(Some(Foo::C), from_name::<Foo>("Gamma"));
+        assert_eq!(None, from_name::<Foo>("Beta"));
+        assert_eq!(None, from_name::<Foo>("b"));
+
+        assert_eq!(Some(Mode::ReadOnly), from_name::<Mode>("read-only"));
+        assert_eq!(Some(Mode::HTTPProxy), from_name::<Mode>("HTTP-Proxy"));
+        assert_eq!(Some(Mode::ReadWrite), from_name::<Mode>("RW"));
+        assert_eq!(None, from_name::<Mode>("ReadOnly"));
+    }
+
+    #[cfg(any(feature = "serde", feature = "arrow"))]
+    #[test]
+    fn test_collect() {
+        use tests::set;
+        use tests::Foo::*;
+        use super::collect;
+
+        let names: Vec<Result<&str, &str>> = vec![Ok("C"), Ok("D"), Ok("A")];
+        assert_eq!(Ok(set(&[A, C])), collect(names.clone(), |_| Ok(())));
+        assert_eq!(Err("D"), collect::<Foo, _, _, _, _>(names, Err));
+        let failed: Vec<Result<&str, &str>> = vec![Ok("A"), Err("failed")];
+        assert_eq!(Err("failed"), collect::<Foo, _, _, _, _>(failed, |_| Ok(())));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(0, edit_distance("Read", "read"));
+        assert_eq!(1, edit_distance("Reed", "Read"));
+        assert_eq!(2, edit_distance("Wirte", "Write"));
+        assert_eq!(3, edit_distance("", "abc"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        assert_eq!(Some("A".to_owned()), suggest::<Foo>("a"));
+        assert_eq!(Some("A".to_owned()), suggest::<Foo>("D"));
+        assert_eq!(None, suggest::<Foo>("Delete"));
+        assert_eq!(Some("read-write".to_owned()), suggest::<Mode>("read-wirte"));
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::borrow;
+use std::fmt;
+use std::hash;
+use std::marker::PhantomData;
+use std::num::NonZeroU32;
+
+use {bit, CLike, EnumSet, Iter};
+
+/// A set which contains at least one element.
+///
+/// The bits are stored in a `NonZeroU32`, so `Option<NonEmptyEnumSet<E>>`
+/// is the same size as `EnumSet<E>`.
+pub struct NonEmptyEnumSet<E> {
+    bits: NonZeroU32,
+    phantom: PhantomData<E>,
+}
+
+impl<E: CLike> NonEmptyEnumSet<E> {
+    /// Returns the set containing only `value`.
+    pub fn new(value: E) -> Self {
+        // `bit` never returns 0.
+        NonEmptyEnumSet { bits: NonZeroU32::new(bit(&value)).unwrap(), phantom: PhantomData }
+    }
+
+    /// Returns the elements of `set`, or `None` if it is empty.
+    pub fn from_set(set: EnumSet<E>) -> Option<Self> {
+        NonZeroU32::new(set.bits).map(|bits| NonEmptyEnumSet { bits, phantom: PhantomData })
+    }
+
+    /// Returns the elements as an `EnumSet`.
+    pub fn get(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.bits.get())
+    }
+
+    /// Returns the number of elements in the set, which is at least one.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.bits.get().count_ones() as usize
+    }
+
+    /// Returns the element with the lowest value.
+    pub fn first(&self) -> E {
+        // Safe because of the invariant that only valid bits are set.
+        unsafe { E::from_u32(self.bits.get().trailing_zeros()) }
+    }
+
+    /// Returns the element with the highest value.
+    pub fn last(&self) -> E {
+        // Safe because of the invariant that only valid bits are set.
+        unsafe { E::from_u32(31 - self.bits.get().leading_zeros()) }
+    }
+
+    /// Returns `true` if the set contains the given enum.
+    pub fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        self.bits.get() & bit(value.borrow()) != 0
+    }
+
+    /// Adds an enum to the set, returning `true` if it was not already
+    /// present.
+    pub fn insert(&mut self, value: E) -> bool {
+        let bit = bit(&value);
+        let result = self.bits.get() & bit == 0;
+        self.bits |= bit;
+        result
+    }
+
+    /// Returns the union of the set and `other`.
+    pub fn union(&self, other: EnumSet<E>) -> Self {
+        NonEmptyEnumSet { bits: self.bits | other.bits, phantom: PhantomData }
+    }
+
+    /// Returns an iterator over the set's elements.
+    pub fn iter(&self) -> Iter<E> {
+        self.get().iter()
+    }
+}
+
+impl<E> Clone for NonEmptyEnumSet<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for NonEmptyEnumSet<E> {}
+
+impl<E> PartialEq for NonEmptyEnumSet<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+
+impl<E> Eq for NonEmptyEnumSet<E> {}
+
+impl<E> hash::Hash for NonEmptyEnumSet<E> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.bits.hash(state);
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for NonEmptyEnumSet<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.get().fmt(fmt)
+    }
+}
+
+impl<E: CLike> From<NonEmptyEnumSet<E>> for EnumSet<E> {
+    fn from(set: NonEmptyEnumSet<E>) -> Self {
+        set.get()
+    }
+}
+
+impl<E: CLike> IntoIterator for NonEmptyEnumSet<E> {
+    type Item = E;
+    type IntoIter = Iter<E>;
+
+    fn into_iter(self) -> Iter<E> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::NonEmptyEnumSet;
+
+    #[test]
+    fn test_size() {
+        assert_eq!(mem::size_of::<EnumSet<Foo>>(), mem::size_of::<Option<NonEmptyEnumSet<Foo>>>());
+    }
+
+    #[test]
+    fn test_new() {
+        let mut s = NonEmptyEnumSet::new(B);
+        assert_eq!(B, s.first());
+        assert_eq!(1, s.len());
+        assert!(s.insert(C));
+        assert!(!s.insert(B));
+        assert_eq!(B, s.first());
+        assert_eq!(C, s.last());
+        assert!(s.contains(C) && !s.contains(A));
+        assert_eq!(vec![B, C], s.iter().collect::<Vec<_>>());
+        assert_eq!(set(&[A, B, C]), s.union(set(&[A])).into());
+    }
+
+    #[test]
+    fn test_from_set() {
+        assert_eq!(None, NonEmptyEnumSet::from_set(EnumSet::<Foo>::new()));
+        let s = NonEmptyEnumSet::from_set(set(&[A, C])).unwrap();
+        assert_eq!(set(&[A, C]), s.get());
+        assert_eq!(A, s.first());
+        assert_eq!("{A, C}", format!("{:?}", s));
+    }
+}
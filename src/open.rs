@@ -0,0 +1,184 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::borrow;
+use std::fmt;
+
+use {universe, CLike, EnumSet, Finite};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+/// A set of a `#[non_exhaustive]` enum which keeps bits of unknown variants.
+///
+/// The bits from `E::VARIANT_COUNT` upwards are reserved for variants added
+/// in later versions of the enum. Decoding bits with `from_bits` retains
+/// them as opaque unknown bits, so they survive being encoded again with
+/// `bits`, while `EnumSet::from_bits_u16` and the other checked
+/// constructors reject them.
+///
+/// ```
+/// use enum_set::{CLike, Finite, OpenEnumSet};
+/// use std::mem;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[non_exhaustive]
+/// #[repr(u32)]
+/// enum Capability {
+///     Compress, Encrypt
+/// }
+///
+/// impl CLike for Capability {
+///     fn to_u32(&self) -> u32 {
+///         *self as u32
+///     }
+///
+///     unsafe fn from_u32(v: u32) -> Capability {
+///         mem::transmute(v)
+///     }
+/// }
+///
+/// unsafe impl Finite for Capability {
+///     const VARIANT_COUNT: u32 = 2;
+/// }
+///
+/// // A peer sent a capability added after this version of the enum.
+/// let mut set = OpenEnumSet::<Capability>::from_bits(0b101);
+/// assert!(set.contains(Capability::Compress));
+/// assert_eq!(0b100, set.unknown_bits());
+///
+/// set.insert(Capability::Encrypt);
+/// assert_eq!(0b111, set.bits());
+/// assert!(set.into_known().is_err());
+/// ```
+pub struct OpenEnumSet<E> {
+    known: EnumSet<E>,
+    unknown: u32,
+}
+
+impl<E: Finite> OpenEnumSet<E> {
+    /// Returns an empty set.
+    pub fn new() -> Self {
+        OpenEnumSet { known: EnumSet::new(), unknown: 0 }
+    }
+
+    /// Returns the set with the given bits, retaining the bits which do not
+    /// correspond to a variant as unknown bits.
+    pub fn from_bits(bits: u32) -> Self {
+        let universe = universe::<E>();
+        OpenEnumSet { known: EnumSet::new_with_bits(bits & universe), unknown: bits & !universe }
+    }
+
+    /// Returns the bits of the set, including the unknown bits.
+    pub fn bits(&self) -> u32 {
+        self.known.bits | self.unknown
+    }
+
+    /// Returns the bits which do not correspond to a variant.
+    pub fn unknown_bits(&self) -> u32 {
+        self.unknown
+    }
+
+    /// Returns the elements of the set which are known variants.
+    pub fn known(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.known.bits)
+    }
+
+    /// Returns the set as an `EnumSet`, or its unknown bits as an error if
+    /// it has any.
+    pub fn into_known(self) -> Result<EnumSet<E>, u32> {
+        if self.unknown == 0 {
+            Ok(self.known)
+        } else {
+            Err(self.unknown)
+        }
+    }
+
+    /// Adds the given value to the set.
+    ///
+    /// Returns `true` if the value was not already present in the set.
+    pub fn insert(&mut self, value: E) -> bool {
+        self.known.insert(value)
+    }
+
+    /// Removes a value from the set.
+    ///
+    /// Returns `true` if the value was present in the set.
+    pub fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        self.known.remove(value)
+    }
+
+    /// Returns `true` if the set contains the given value.
+    pub fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        self.known.contains(value)
+    }
+
+    /// Removes the unknown bits from the set.
+    pub fn clear_unknown(&mut self) {
+        self.unknown = 0;
+    }
+}
+
+impl<E: Finite> Default for OpenEnumSet<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Finite> From<EnumSet<E>> for OpenEnumSet<E> {
+    fn from(known: EnumSet<E>) -> Self {
+        OpenEnumSet { known, unknown: 0 }
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for OpenEnumSet<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("OpenEnumSet")
+           .field("known", &self.known)
+           .field("unknown", &format_args!("{:#x}", self.unknown))
+           .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use super::OpenEnumSet;
+
+    #[test]
+    fn test_unknown_bits() {
+        let mut e = OpenEnumSet::<Foo>::from_bits(0b11010);
+        assert_eq!(set(&[B]), e.known());
+        assert_eq!(0b11000, e.unknown_bits());
+        assert_eq!(Err(0b11000), e.into_known());
+
+        assert!(e.insert(A));
+        assert!(e.contains(A));
+        assert!(e.remove(B));
+        assert_eq!(0b11001, e.bits());
+
+        e.clear_unknown();
+        assert_eq!(Ok(set(&[A])), e.into_known());
+    }
+
+    #[test]
+    fn test_from_known() {
+        let e = OpenEnumSet::from(set(&[A, C]));
+        assert_eq!(0b101, e.bits());
+        assert_eq!(OpenEnumSet::<Foo>::from_bits(0b101), e);
+        assert_eq!(OpenEnumSet::new(), OpenEnumSet::<Foo>::from_bits(0));
+    }
+
+    #[test]
+    fn test_debug() {
+        let e = OpenEnumSet::<Foo>::from_bits(0b1001);
+        assert_eq!("OpenEnumSet { known: {A}, unknown: 0x8 }", format!("{:?}", e));
+    }
+}
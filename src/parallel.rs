@@ -0,0 +1,66 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Collecting parallel iterators into sets with `rayon`.
+//!
+//! Each thread collects its elements into a set of its own, and the partial
+//! sets are then combined with a union.
+
+use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator};
+
+use {bit, CLike, EnumSet};
+
+fn collect<E, I>(par_iter: I) -> u32
+    where E: CLike + Send,
+          I: IntoParallelIterator<Item = E>
+{
+    par_iter.into_par_iter()
+            .fold(|| 0, |bits, element| bits | bit(&element))
+            .reduce(|| 0, |a, b| a | b)
+}
+
+impl<E: CLike + Send> FromParallelIterator<E> for EnumSet<E> {
+    fn from_par_iter<I: IntoParallelIterator<Item = E>>(par_iter: I) -> Self {
+        EnumSet::new_with_bits(collect(par_iter))
+    }
+}
+
+impl<E: CLike + Send> ParallelExtend<E> for EnumSet<E> {
+    fn par_extend<I: IntoParallelIterator<Item = E>>(&mut self, par_iter: I) {
+        self.bits |= collect(par_iter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::iter::{IntoParallelIterator, ParallelExtend, ParallelIterator};
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_collect() {
+        let e: EnumSet<Foo> = (0..1000u32).into_par_iter()
+                                          .map(|i| if i % 2 == 0 { A } else { C })
+                                          .collect();
+        assert_eq!(set(&[A, C]), e);
+        let e: EnumSet<Foo> = Vec::<Foo>::new().into_par_iter().collect();
+        assert!(e.is_empty());
+    }
+
+    #[test]
+    fn test_par_extend() {
+        let mut e = set(&[B]);
+        e.par_extend(vec![A, A, B]);
+        assert_eq!(set(&[A, B]), e);
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parsing of sets from lists of variant names.
+
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use {names, EnumSet, Finite};
+
+/// An error returned when parsing a set from a list of names fails because
+/// a name is not a variant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseNamesError {
+    offset: usize,
+    token: String,
+    suggestion: Option<String>,
+}
+
+impl ParseNamesError {
+    /// Returns the byte offset of the unknown name in the input.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the unknown name.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Returns the name of the variant closest to the unknown name, if it is
+    /// close enough to be a likely typo.
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_ref().map(|suggestion| &suggestion[..])
+    }
+}
+
+impl fmt::Display for ParseNamesError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "unknown variant `{}` at offset {}", self.token, self.offset)?;
+        if let Some(ref suggestion) = self.suggestion {
+            write!(fmt, ", did you mean `{}`?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for ParseNamesError {}
+
+/// Parses variant names separated by commas or whitespace.
+pub fn parse_names<E: Finite + fmt::Debug>(s: &str) -> Result<EnumSet<E>, ParseNamesError> {
+    let mut set = EnumSet::new();
+    let mut offset = 0;
+    for token in s.split(|c: char| c == ',' || c.is_whitespace()) {
+        if !token.is_empty() {
+            match names::from_name::<E>(token) {
+                Some(variant) => {
+                    set.insert(variant);
+                }
+                None => {
+                    return Err(ParseNamesError {
+                        offset,
+                        token: token.to_owned(),
+                        suggestion: names::suggest::<E>(token),
+                    })
+                }
+            }
+        }
+        // Every separator is a single byte or a whitespace character.
+        offset += token.len() + s[offset + token.len()..].chars().next().map_or(0, char::len_utf8);
+    }
+    Ok(set)
+}
+
+impl<E: Finite + fmt::Debug> FromStr for EnumSet<E> {
+    type Err = ParseNamesError;
+
+    /// Parses a set from variant names separated by commas or whitespace.
+    ///
+    /// Names are the `Debug` representations of the variants in
+    /// `Finite::NAME_CASE`.
+    fn from_str(s: &str) -> Result<Self, ParseNamesError> {
+        parse_names(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(Ok(set(&[A, C])), "C, A".parse());
+        assert_eq!(Ok(set(&[B])), " B\u{3000}B,".parse());
+        assert_eq!(Ok(set(&[])), "".parse());
+        assert_eq!(Ok(set(&[A, C])), "Gamma Alpha".parse());
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = "A,\u{3000}c".parse::<EnumSet<Foo>>().unwrap_err();
+        assert_eq!(5, err.offset());
+        assert_eq!("c", err.token());
+        assert_eq!(Some("C"), err.suggestion());
+        assert_eq!("unknown variant `c` at offset 5, did you mean `C`?", err.to_string());
+
+        let err = "A Delete".parse::<EnumSet<Foo>>().unwrap_err();
+        assert_eq!(2, err.offset());
+        assert_eq!(None, err.suggestion());
+        assert_eq!("unknown variant `Delete` at offset 2", err.to_string());
+    }
+}
@@ -0,0 +1,254 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A map keyed by sets, stored as a dense table.
+
+use std::fmt;
+use std::iter;
+use std::marker::PhantomData;
+use std::slice;
+
+use {CLike, EnumSet, Finite};
+
+/// A map from sets of `E` to values of type `V`.
+///
+/// The map allocates a slot for every possible subset of the variants of
+/// `E`, that is `2^E::VARIANT_COUNT` slots, and indexes them directly by the
+/// bit mask of the set. This makes it suitable for memoizing results of
+/// dynamic programming over subsets of small enums.
+pub struct PowerSetMap<E, V> {
+    values: Vec<Option<V>>,
+    len: usize,
+    phantom: PhantomData<E>,
+}
+
+impl<E: Finite, V> PowerSetMap<E, V> {
+    /// Returns an empty `PowerSetMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `2^E::VARIANT_COUNT` does not fit in a `usize`, which is
+    /// the case for enums with 32 variants on 32-bit targets. Note that an
+    /// enum with 32 variants needs `2^32` slots on any target.
+    pub fn new() -> Self {
+        let slots = 1usize.checked_shl(E::VARIANT_COUNT)
+                          .expect("PowerSetMap::new: too many variants");
+        PowerSetMap {
+            values: iter::repeat_with(|| None).take(slots).collect(),
+            len: 0,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<E: CLike, V> PowerSetMap<E, V> {
+    /// Returns the number of sets with a value in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all values from the map.
+    pub fn clear(&mut self) {
+        for value in &mut self.values {
+            *value = None;
+        }
+        self.len = 0;
+    }
+
+    /// Returns a reference to the value for `set`.
+    pub fn get(&self, set: EnumSet<E>) -> Option<&V> {
+        self.values[set.bits as usize].as_ref()
+    }
+
+    /// Returns a mutable reference to the value for `set`.
+    pub fn get_mut(&mut self, set: EnumSet<E>) -> Option<&mut V> {
+        self.values[set.bits as usize].as_mut()
+    }
+
+    /// Returns `true` if the map contains a value for `set`.
+    pub fn contains_key(&self, set: EnumSet<E>) -> bool {
+        self.values[set.bits as usize].is_some()
+    }
+
+    /// Sets the value for `set`, returning the previous value.
+    pub fn insert(&mut self, set: EnumSet<E>, value: V) -> Option<V> {
+        let old = self.values[set.bits as usize].replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Removes the value for `set` from the map, returning it.
+    pub fn remove(&mut self, set: EnumSet<E>) -> Option<V> {
+        let old = self.values[set.bits as usize].take();
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    /// Returns the entry for `set`, for in-place manipulation.
+    pub fn entry(&mut self, set: EnumSet<E>) -> Entry<'_, E, V> {
+        Entry {
+            slot: &mut self.values[set.bits as usize],
+            set,
+            len: &mut self.len,
+        }
+    }
+
+    /// Returns an iterator over the sets with a value and their values, in
+    /// order of their bit masks.
+    pub fn iter(&self) -> Iter<'_, E, V> {
+        Iter { inner: self.values.iter().enumerate(), phantom: PhantomData }
+    }
+}
+
+impl<E: Finite, V> Default for PowerSetMap<E, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E, V: Clone> Clone for PowerSetMap<E, V> {
+    fn clone(&self) -> Self {
+        PowerSetMap { values: self.values.clone(), len: self.len, phantom: PhantomData }
+    }
+}
+
+impl<E: CLike + fmt::Debug, V: fmt::Debug> fmt::Debug for PowerSetMap<E, V> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_map().entries(self).finish()
+    }
+}
+
+impl<'a, E: CLike, V> IntoIterator for &'a PowerSetMap<E, V> {
+    type Item = (EnumSet<E>, &'a V);
+    type IntoIter = Iter<'a, E, V>;
+    fn into_iter(self) -> Iter<'a, E, V> { self.iter() }
+}
+
+/// A view into a single slot of a `PowerSetMap`.
+pub struct Entry<'a, E: 'a, V: 'a> {
+    set: EnumSet<E>,
+    slot: &'a mut Option<V>,
+    len: &'a mut usize,
+}
+
+impl<'a, E: CLike, V> Entry<'a, E, V> {
+    /// Returns the set of this entry.
+    pub fn key(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.set.bits)
+    }
+
+    /// Inserts `default` if the entry is empty, and returns a mutable
+    /// reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default` if the entry is empty, and returns a
+    /// mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        if self.slot.is_none() {
+            *self.len += 1;
+        }
+        self.slot.get_or_insert_with(default)
+    }
+
+    /// Inserts the default value if the entry is empty, and returns a
+    /// mutable reference to the value.
+    pub fn or_default(self) -> &'a mut V where V: Default {
+        self.or_insert_with(V::default)
+    }
+}
+
+/// An iterator over the entries of a `PowerSetMap`.
+pub struct Iter<'a, E, V: 'a> {
+    inner: iter::Enumerate<slice::Iter<'a, Option<V>>>,
+    phantom: PhantomData<*mut E>,
+}
+
+impl<'a, E, V> Clone for Iter<'a, E, V> {
+    fn clone(&self) -> Self {
+        Iter { inner: self.inner.clone(), phantom: PhantomData }
+    }
+}
+
+impl<'a, E: CLike, V> Iterator for Iter<'a, E, V> {
+    type Item = (EnumSet<E>, &'a V);
+
+    fn next(&mut self) -> Option<(EnumSet<E>, &'a V)> {
+        for (bits, value) in &mut self.inner {
+            if let Some(ref value) = *value {
+                return Some((EnumSet::new_with_bits(bits as u32), value));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::PowerSetMap;
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut map = PowerSetMap::new();
+        assert!(map.is_empty());
+        assert_eq!(None, map.insert(set(&[A, C]), 1));
+        assert_eq!(None, map.insert(EnumSet::new(), 2));
+        assert_eq!(Some(1), map.insert(set(&[A, C]), 3));
+        assert_eq!(2, map.len());
+
+        assert_eq!(Some(&3), map.get(set(&[A, C])));
+        assert_eq!(Some(&2), map.get(EnumSet::new()));
+        assert_eq!(None, map.get(set(&[A, B, C])));
+
+        *map.get_mut(set(&[A, C])).unwrap() += 1;
+        assert_eq!(Some(4), map.remove(set(&[A, C])));
+        assert_eq!(None, map.remove(set(&[A, C])));
+        assert_eq!(1, map.len());
+
+        map.clear();
+        assert!(map.is_empty());
+        assert!(!map.contains_key(EnumSet::new()));
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut map: PowerSetMap<Foo, u32> = PowerSetMap::new();
+        *map.entry(set(&[B])).or_insert(10) += 1;
+        *map.entry(set(&[B])).or_insert(10) += 1;
+        assert_eq!(Some(&12), map.get(set(&[B])));
+        assert_eq!(0, *map.entry(set(&[C])).or_default());
+        assert_eq!(set(&[A]), map.entry(set(&[A])).key());
+        assert_eq!(2, map.len());
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut map = PowerSetMap::new();
+        map.insert(set(&[A, B, C]), 'x');
+        map.insert(set(&[B]), 'y');
+        let entries: Vec<_> = map.iter().collect();
+        assert_eq!(vec![(set(&[B]), &'y'), (set(&[A, B, C]), &'x')], entries);
+        assert_eq!("{{B}: 'y', {A, B, C}: 'x'}", format!("{:?}", map));
+    }
+}
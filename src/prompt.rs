@@ -0,0 +1,79 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+
+use dialoguer::{self, MultiSelect};
+
+use {names, Describe, EnumSet, Finite};
+
+/// Returns the prompt items for the variants of `E`, checked if `set`
+/// contains them.
+fn items<E, F>(set: &EnumSet<E>, mut label: F) -> Vec<(String, bool)>
+    where E: Finite + fmt::Debug, F: FnMut(E, String) -> String
+{
+    names::variants::<E>().map(|(variant, name)| {
+        let checked = set.contains(&variant);
+        (label(variant, name), checked)
+    }).collect()
+}
+
+fn from_selection<E: Finite>(selection: Vec<usize>) -> EnumSet<E> {
+    EnumSet::new_with_bits(selection.into_iter().fold(0, |bits, index| bits | 1 << index))
+}
+
+impl<E: Finite + fmt::Debug> EnumSet<E> {
+    /// Asks the user to pick variants from a multi-select prompt listing
+    /// every variant of `E` by name, and returns the chosen set.
+    ///
+    /// The variants in the set are checked initially.
+    pub fn interact_multi_select(&self, prompt: &str) -> dialoguer::Result<Self> {
+        let selection = MultiSelect::new()
+            .with_prompt(prompt)
+            .items_checked(items(self, |_, name| name))
+            .interact()?;
+        Ok(from_selection(selection))
+    }
+}
+
+impl<E: Describe + Finite + fmt::Debug> EnumSet<E> {
+    /// Like `interact_multi_select`, but lists the description of each
+    /// variant after its name.
+    pub fn interact_multi_select_described(&self, prompt: &str) -> dialoguer::Result<Self> {
+        let selection = MultiSelect::new()
+            .with_prompt(prompt)
+            .items_checked(items(self, |variant, name| {
+                format!("{} - {}", name, variant.description())
+            }))
+            .interact()?;
+        Ok(from_selection(selection))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use super::{from_selection, items};
+
+    #[test]
+    fn test_items() {
+        let items = items(&set(&[A, C]), |variant, name| format!("{}{}", name, variant as u32));
+        assert_eq!(vec![("A0".to_owned(), true), ("B1".to_owned(), false), ("C2".to_owned(), true)],
+                   items);
+    }
+
+    #[test]
+    fn test_from_selection() {
+        assert_eq!(set(&[A, C]), from_selection(vec![0, 2]));
+        assert!(from_selection::<Foo>(vec![]).is_empty());
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions between sets and Python `set`s.
+//!
+//! Each element is converted with the conversions of the enum itself, so a
+//! set becomes a Python `set` of whatever the variants convert to, such as
+//! enum members or strings.
+
+use pyo3::conversion::FromPyObjectOwned;
+use pyo3::types::{PyAnyMethods, PyFrozenSet, PyFrozenSetMethods, PySet, PySetMethods};
+use pyo3::{Borrowed, Bound, FromPyObject, IntoPyObject, PyAny, PyErr, Python};
+
+use {CLike, EnumSet};
+
+impl<'py, E: CLike + IntoPyObject<'py>> IntoPyObject<'py> for EnumSet<E> {
+    type Target = PySet;
+    type Output = Bound<'py, PySet>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Bound<'py, PySet>, PyErr> {
+        PySet::new(py, self.iter())
+    }
+}
+
+impl<'py, E: CLike + IntoPyObject<'py>> IntoPyObject<'py> for &EnumSet<E> {
+    type Target = PySet;
+    type Output = Bound<'py, PySet>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Bound<'py, PySet>, PyErr> {
+        PySet::new(py, self.iter())
+    }
+}
+
+/// Extracts a set from a Python `set` or `frozenset`.
+impl<'a, 'py, E: CLike + FromPyObjectOwned<'py>> FromPyObject<'a, 'py> for EnumSet<E> {
+    type Error = PyErr;
+
+    fn extract(ob: Borrowed<'a, 'py, PyAny>) -> Result<Self, PyErr> {
+        let mut result = EnumSet::new();
+        match ob.cast::<PySet>() {
+            Ok(set) => {
+                for item in set.iter() {
+                    result.insert(item.extract().map_err(Into::into)?);
+                }
+            }
+            Err(err) => {
+                let frozen_set = ob.cast::<PyFrozenSet>().map_err(|_| PyErr::from(err))?;
+                for item in frozen_set.iter() {
+                    result.insert(item.extract().map_err(Into::into)?);
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::types::{PyAnyMethods, PyFrozenSet, PyList, PySet, PySetMethods};
+    use pyo3::{Borrowed, Bound, FromPyObject, IntoPyObject, PyAny, PyErr, Python};
+    use pyo3::exceptions::PyValueError;
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    impl<'py> IntoPyObject<'py> for Foo {
+        type Target = <&'static str as IntoPyObject<'py>>::Target;
+        type Output = <&'static str as IntoPyObject<'py>>::Output;
+        type Error = <&'static str as IntoPyObject<'py>>::Error;
+
+        fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+            match self { A => "A", B => "B", C => "C" }.into_pyobject(py)
+        }
+    }
+
+    impl<'a, 'py> FromPyObject<'a, 'py> for Foo {
+        type Error = PyErr;
+
+        fn extract(ob: Borrowed<'a, 'py, PyAny>) -> Result<Self, PyErr> {
+            match &*ob.extract::<String>()? {
+                "A" => Ok(A),
+                "B" => Ok(B),
+                "C" => Ok(C),
+                _ => Err(PyValueError::new_err("unknown variant")),
+            }
+        }
+    }
+
+    #[test]
+    fn test_into_pyobject() {
+        Python::initialize();
+        Python::attach(|py| {
+            let py_set: Bound<PySet> = set(&[A, C]).into_pyobject(py).unwrap();
+            assert_eq!(2, py_set.len());
+            assert!(py_set.contains("A").unwrap());
+            assert!(py_set.contains("C").unwrap());
+            assert!((&set(&[])).into_pyobject(py).unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_extract() {
+        Python::initialize();
+        Python::attach(|py| {
+            let py_set = PySet::new(py, ["B", "C"]).unwrap();
+            assert_eq!(set(&[B, C]), py_set.extract::<EnumSet<Foo>>().unwrap());
+
+            let py_set = PyFrozenSet::new(py, ["A"]).unwrap();
+            assert_eq!(set(&[A]), py_set.extract::<EnumSet<Foo>>().unwrap());
+
+            let py_set = PySet::new(py, ["A", "D"]).unwrap();
+            assert!(py_set.extract::<EnumSet<Foo>>().is_err());
+
+            let py_list = PyList::new(py, ["A"]).unwrap();
+            assert!(py_list.extract::<EnumSet<Foo>>().is_err());
+        });
+    }
+}
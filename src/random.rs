@@ -0,0 +1,85 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rand::{Rng, RngExt};
+
+use {EnumSet, Finite};
+
+impl<E: Finite> EnumSet<E> {
+    /// Returns a random set including every variant independently with
+    /// probability `p`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not between `0.0` and `1.0`.
+    pub fn random_with_density<R: Rng + ?Sized>(rng: &mut R, p: f64) -> Self {
+        Self::random_with_densities(rng, |_| p)
+    }
+
+    /// Returns a random set including every variant `e` independently with
+    /// probability `density(e)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a probability is not between `0.0` and `1.0`.
+    pub fn random_with_densities<R, F>(rng: &mut R, mut density: F) -> Self
+        where R: Rng + ?Sized,
+              F: FnMut(E) -> f64
+    {
+        let mut bits = 0;
+        for index in 0..E::VARIANT_COUNT {
+            // Safe because every value below `VARIANT_COUNT` is a variant.
+            let variant = unsafe { E::from_u32(index) };
+            if rng.random_bool(density(variant)) {
+                bits |= 1 << index;
+            }
+        }
+        Self::new_with_bits(bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use tests::Foo;
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_random_with_density() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(EnumSet::<Foo>::random_with_density(&mut rng, 0.0).is_empty());
+        assert_eq!(3, EnumSet::<Foo>::random_with_density(&mut rng, 1.0).len());
+
+        let total: usize = (0..1000)
+            .map(|_| EnumSet::<Foo>::random_with_density(&mut rng, 0.5).len())
+            .sum();
+        assert!(total > 1300 && total < 1700);
+    }
+
+    #[test]
+    fn test_random_with_densities() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let set = EnumSet::random_with_densities(&mut rng, |e| if e == B { 1.0 } else { 0.0 });
+            assert_eq!(vec![B], set.iter().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_density() {
+        let mut rng = StdRng::seed_from_u64(0);
+        EnumSet::<Foo>::random_with_density(&mut rng, 1.5);
+    }
+}
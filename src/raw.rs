@@ -0,0 +1,132 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Non-generic implementations of set operations on bit masks.
+//!
+//! The generic methods of `EnumSet` and its iterators are thin wrappers
+//! around these, so the loops are compiled once rather than once per enum.
+
+use std::cmp::Ordering;
+use std::num;
+
+/// Returns the index of the first element of `bits` after the one at
+/// `index`, wrapping around to the first element.
+pub fn next_after(bits: u32, index: u32) -> Option<u32> {
+    let value = 1u32 << index;
+    let after = bits & !(value | (value - 1));
+    let next = if after != 0 { after } else { bits };
+    if next == 0 { None } else { Some(next.trailing_zeros()) }
+}
+
+/// Returns the index of the last element of `bits` before the one at
+/// `index`, wrapping around to the last element.
+pub fn prev_before(bits: u32, index: u32) -> Option<u32> {
+    let before = bits & ((1u32 << index) - 1);
+    let prev = if before != 0 { before } else { bits };
+    if prev == 0 { None } else { Some(31 - prev.leading_zeros()) }
+}
+
+/// Returns the sum of `weights[i]` over the elements `i` of `bits`.
+pub fn score(mut bits: u32, weights: &[i64]) -> i64 {
+    let mut total = 0;
+    while bits != 0 {
+        total += weights[bits.trailing_zeros() as usize];
+        bits &= bits - 1;
+    }
+    total
+}
+
+/// Returns the sum of `weights[i]` over the elements `i` of `bits`.
+pub fn score_f64(mut bits: u32, weights: &[f64]) -> f64 {
+    let mut total = 0.0;
+    while bits != 0 {
+        total += weights[bits.trailing_zeros() as usize];
+        bits &= bits - 1;
+    }
+    total
+}
+
+/// Compares `a` and `b` like the sorted lists of the indices of their
+/// elements.
+pub fn cmp_elements(a: u32, b: u32) -> Ordering {
+    let differing = a ^ b;
+    if differing == 0 {
+        return Ordering::Equal;
+    }
+    // Both lists agree up to the first differing element. The set which
+    // contains it is smaller, unless the other list ends there.
+    let first = differing & differing.wrapping_neg();
+    let above = !(first | (first - 1));
+    let (with, without) = if a & first != 0 { (a, b) } else { (b, a) };
+    let ordering = if without & above != 0 { Ordering::Less } else { Ordering::Greater };
+    if with == a { ordering } else { ordering.reverse() }
+}
+
+/// Returns the submask of `free` following `current` in increasing order.
+pub fn next_submask(current: u32, free: u32) -> Option<u32> {
+    // Adding one to the free bits with every other bit set carries over the
+    // unset free bits, giving the next submask in increasing order.
+    let following = (current | !free).wrapping_add(1) & free;
+    if following == 0 { None } else { Some(following) }
+}
+
+/// Returns the mask over `count` positions following `current` in the order
+/// of `EnumSet::subsets_by_len`, where `len` is the number of positions set
+/// in `current` and is updated as the length grows.
+pub fn next_combination(current: u64, count: u32, len: &mut u32) -> Option<u64> {
+    if current == 0 {
+        // Only the empty subset has length zero.
+        return if count == 0 { None } else { *len = 1; Some(1) };
+    }
+    // The next mask with the same number of bits set, in increasing order.
+    let lowest = current & current.wrapping_neg();
+    let ripple = current + lowest;
+    let following = (((ripple ^ current) >> 2) / lowest) | ripple;
+    if following >> count == 0 {
+        Some(following)
+    } else if *len < count {
+        *len += 1;
+        Some((1 << *len) - 1)
+    } else {
+        None
+    }
+}
+
+/// Scatters the positions set in `positions` over the bits of `elements`,
+/// so that position `i` selects the `i`th lowest bit of `elements`.
+pub fn scatter(positions: u64, mut elements: u32) -> u32 {
+    let mut bits = 0;
+    let mut position = 0;
+    while elements != 0 {
+        let lowest = elements & elements.wrapping_neg();
+        if positions & (1 << position) != 0 {
+            bits |= lowest;
+        }
+        elements &= elements - 1;
+        position += 1;
+    }
+    bits
+}
+
+/// Parses a mask in the given radix, with an optional `0b`, `0o` or `0x`
+/// prefix matching the radix.
+pub fn parse_radix(s: &str, radix: u32) -> Result<u32, num::ParseIntError> {
+    let prefix = match radix {
+        2 => Some("0b"),
+        8 => Some("0o"),
+        16 => Some("0x"),
+        _ => None,
+    };
+    let digits = match prefix {
+        Some(prefix) if s.is_char_boundary(2) && s[..2].eq_ignore_ascii_case(prefix) => &s[2..],
+        _ => s,
+    };
+    u32::from_str_radix(digits, radix)
+}
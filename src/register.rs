@@ -0,0 +1,240 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions between sets and hardware register values.
+//!
+//! Datasheets number register bits either from the least significant bit
+//! (`Lsb0`) or from the most significant bit (`Msb0`) of a register of a
+//! fixed width. The variant with value `i` is mapped to bit `i` in the chosen
+//! numbering.
+//!
+//! ```
+//! use enum_set::{CLike, EnumSet};
+//! use enum_set::register::Msb0;
+//! use std::mem;
+//!
+//! #[derive(Clone, Copy, Debug, PartialEq)]
+//! #[repr(u32)]
+//! enum Status {
+//!     Ready, Busy, Error
+//! }
+//!
+//! impl CLike for Status {
+//!     fn to_u32(&self) -> u32 {
+//!         *self as u32
+//!     }
+//!
+//!     unsafe fn from_u32(v: u32) -> Status {
+//!         mem::transmute(v)
+//!     }
+//! }
+//!
+//! let mut set = EnumSet::new();
+//! set.insert(Status::Ready);
+//! assert_eq!(0b1000_0000, set.to_register::<Msb0>(8));
+//! ```
+//!
+//! `read_register`, `write_register` and `modify_register` access memory
+//! mapped registers with volatile operations, using `Lsb0` numbering.
+
+use std::ptr;
+
+use {universe, CLike, EnumSet, Finite};
+
+/// A numbering of the bits of a register.
+pub trait BitOrder {
+    /// Converts `bits`, numbered from the least significant bit, into a
+    /// register value of `width` bits using this numbering.
+    fn from_lsb0(bits: u32, width: u32) -> u32;
+
+    /// Converts a register value of `width` bits using this numbering into
+    /// bits numbered from the least significant bit.
+    fn to_lsb0(value: u32, width: u32) -> u32;
+}
+
+/// Bit `0` is the least significant bit of the register.
+#[derive(Copy, Clone, Debug)]
+pub enum Lsb0 {}
+
+/// Bit `0` is the most significant bit of the register.
+#[derive(Copy, Clone, Debug)]
+pub enum Msb0 {}
+
+impl BitOrder for Lsb0 {
+    fn from_lsb0(bits: u32, _width: u32) -> u32 {
+        bits
+    }
+
+    fn to_lsb0(value: u32, _width: u32) -> u32 {
+        value
+    }
+}
+
+impl BitOrder for Msb0 {
+    fn from_lsb0(bits: u32, width: u32) -> u32 {
+        bits.reverse_bits() >> (32 - width)
+    }
+
+    fn to_lsb0(value: u32, width: u32) -> u32 {
+        value.reverse_bits() >> (32 - width)
+    }
+}
+
+fn fits(bits: u32, width: u32) -> bool {
+    bits.checked_shr(width).unwrap_or(0) == 0
+}
+
+fn check_width(width: u32) {
+    assert!((1..=32).contains(&width), "Register width must be between 1 and 32 bits.");
+}
+
+impl<E: CLike> EnumSet<E> {
+    /// Returns the value of a register of `width` bits with the bits of the
+    /// set's elements set, numbered according to `O`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is not between 1 and 32, or if the set contains an
+    /// element which does not fit in the register.
+    pub fn to_register<O: BitOrder>(&self, width: u32) -> u32 {
+        check_width(width);
+        assert!(fits(self.bits, width), "EnumSet contains a value out of the register bounds.");
+        O::from_lsb0(self.bits, width)
+    }
+}
+
+impl<E: Finite> EnumSet<E> {
+    /// Creates a set from the value of a register of `width` bits, numbered
+    /// according to `O`.
+    ///
+    /// Returns `None` if the value has bits set outside of the register or
+    /// which do not correspond to a variant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is not between 1 and 32.
+    pub fn from_register<O: BitOrder>(value: u32, width: u32) -> Option<Self> {
+        check_width(width);
+        if !fits(value, width) {
+            return None;
+        }
+        let bits = O::to_lsb0(value, width);
+        if bits & !universe::<E>() != 0 {
+            return None;
+        }
+        Some(Self::new_with_bits(bits))
+    }
+}
+
+/// Reads the set stored in the register at `register` with a volatile read.
+///
+/// Bits which do not correspond to a variant are ignored.
+///
+/// # Safety
+///
+/// `register` must be valid for volatile reads of a `u32` and properly
+/// aligned, as for `ptr::read_volatile`.
+pub unsafe fn read_register<E: Finite>(register: *const u32) -> EnumSet<E> {
+    EnumSet::new_with_bits(ptr::read_volatile(register) & universe::<E>())
+}
+
+/// Writes the bits of `set` to the register at `register` with a volatile
+/// write, clearing every other bit.
+///
+/// # Safety
+///
+/// `register` must be valid for volatile writes of a `u32` and properly
+/// aligned, as for `ptr::write_volatile`.
+pub unsafe fn write_register<E: CLike>(register: *mut u32, set: EnumSet<E>) {
+    ptr::write_volatile(register, set.bits);
+}
+
+/// Reads the set stored in the register at `register`, lets `f` modify it
+/// and writes it back, with one volatile read and one volatile write.
+///
+/// Bits which do not correspond to a variant are written back unchanged.
+/// The read-modify-write is not atomic.
+///
+/// # Safety
+///
+/// `register` must be valid for volatile reads and writes of a `u32` and
+/// properly aligned, as for `ptr::read_volatile` and `ptr::write_volatile`.
+pub unsafe fn modify_register<E, F>(register: *mut u32, f: F)
+    where E: Finite,
+          F: FnOnce(&mut EnumSet<E>)
+{
+    let value = ptr::read_volatile(register);
+    let mut set = EnumSet::new_with_bits(value & universe::<E>());
+    f(&mut set);
+    ptr::write_volatile(register, (value & !universe::<E>()) | set.bits);
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::Foo;
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::{modify_register, read_register, write_register, Lsb0, Msb0};
+
+    #[test]
+    fn test_lsb0() {
+        let mut e: EnumSet<Foo> = EnumSet::new();
+        e.insert(A);
+        e.insert(C);
+        assert_eq!(0b101, e.to_register::<Lsb0>(3));
+        assert_eq!(0b101, e.to_register::<Lsb0>(32));
+        assert_eq!(Some(e), EnumSet::from_register::<Lsb0>(0b101, 8));
+    }
+
+    #[test]
+    fn test_msb0() {
+        let mut e: EnumSet<Foo> = EnumSet::new();
+        e.insert(A);
+        e.insert(B);
+        assert_eq!(0b1100_0000, e.to_register::<Msb0>(8));
+        assert_eq!(0xC000_0000, e.to_register::<Msb0>(32));
+        assert_eq!(Some(e), EnumSet::from_register::<Msb0>(0b1100_0000, 8));
+        assert_eq!(Some(e), EnumSet::from_register::<Msb0>(0xC000_0000, 32));
+    }
+
+    #[test]
+    fn test_from_register_invalid() {
+        assert_eq!(None, EnumSet::<Foo>::from_register::<Lsb0>(0b1000, 8));
+        assert_eq!(None, EnumSet::<Foo>::from_register::<Lsb0>(0x100, 8));
+        assert_eq!(None, EnumSet::<Foo>::from_register::<Msb0>(0b0001_0000, 8));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_to_register_too_narrow() {
+        let mut e: EnumSet<Foo> = EnumSet::new();
+        e.insert(C);
+        e.to_register::<Msb0>(2);
+    }
+
+    #[test]
+    fn test_volatile() {
+        let mut register: u32 = 0b1_0101;
+        let e: EnumSet<Foo> = unsafe { read_register(&register) };
+        assert_eq!([A, C].iter().collect::<EnumSet<_>>(), e);
+
+        unsafe {
+            modify_register(&mut register, |set: &mut EnumSet<Foo>| {
+                set.remove(A);
+                set.insert(B);
+            });
+        }
+        assert_eq!(0b1_0110, register);
+
+        unsafe { write_register(&mut register, e) };
+        assert_eq!(0b101, register);
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parsing of sets from Rocket forms and path parameters.
+//!
+//! A set is written as variant names separated by commas, such as
+//! `features=Metrics,Cache` or `/features/Metrics,Cache`. A form field may
+//! also be repeated, as in `features=Metrics&features=Cache`, and the names
+//! of every occurrence are merged. Names are the `Debug` representations of
+//! the variants in `Finite::NAME_CASE`. An unknown name is a validation
+//! error, which Rocket reports as `422 Unprocessable Entity` for forms.
+
+use std::fmt;
+use std::future::{self, Future};
+use std::pin::Pin;
+
+use rocket::form::error::{Entity, ErrorKind};
+use rocket::form::{self, DataField, FromForm, Options, ValueField};
+use rocket::request::FromParam;
+
+use parse::{parse_names, ParseNamesError};
+use {EnumSet, Finite};
+
+fn validation_error<'v>(error: ParseNamesError) -> form::Error<'v> {
+    form::Error::validation(error.to_string())
+}
+
+/// The state of a set while the fields of a form are pushed into it.
+pub struct FormContext<'v, E> {
+    set: EnumSet<E>,
+    errors: form::Errors<'v>,
+    pushed: bool,
+}
+
+impl<'v, E: Finite + fmt::Debug + Send> FromForm<'v> for EnumSet<E> {
+    type Context = FormContext<'v, E>;
+
+    fn init(_: Options) -> FormContext<'v, E> {
+        FormContext { set: EnumSet::new(), errors: form::Errors::new(), pushed: false }
+    }
+
+    fn push_value(context: &mut FormContext<'v, E>, field: ValueField<'v>) {
+        context.pushed = true;
+        match parse_names(field.value) {
+            Ok(set) => context.set = context.set.union(set),
+            Err(error) => {
+                let error = validation_error(error).with_name(field.name).with_value(field.value);
+                context.errors.push(error);
+            }
+        }
+    }
+
+    // Expanded by hand from the `async_trait` declaration of the method.
+    fn push_data<'c, 'd, 'f>(context: &'c mut FormContext<'v, E>, field: DataField<'v, 'd>)
+                             -> Pin<Box<dyn Future<Output = ()> + Send + 'f>>
+        where 'v: 'f, 'c: 'f, 'd: 'f, Self: 'f
+    {
+        context.pushed = true;
+        let error = form::Error::from(ErrorKind::Unexpected).with_entity(Entity::DataField);
+        context.errors.push(error.with_name(field.name));
+        Box::pin(future::ready(()))
+    }
+
+    fn finalize(context: FormContext<'v, E>) -> form::Result<'v, Self> {
+        if !context.errors.is_empty() {
+            Err(context.errors)
+        } else if !context.pushed {
+            Err(ErrorKind::Missing)?
+        } else {
+            Ok(context.set)
+        }
+    }
+}
+
+impl<'a, E: Finite + fmt::Debug> FromParam<'a> for EnumSet<E> {
+    type Error = form::Errors<'a>;
+
+    fn from_param(param: &'a str) -> form::Result<'a, Self> {
+        parse_names(param).map_err(|error| validation_error(error).with_value(param).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::form::Form;
+    use rocket::request::FromParam;
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_from_form() {
+        assert_eq!(set(&[A, C]), Form::parse("features=A, C").unwrap());
+        assert_eq!(set(&[]), Form::<EnumSet<Foo>>::parse("features=").unwrap());
+        assert!(Form::<EnumSet<Foo>>::parse("features=A,D").is_err());
+        assert!(Form::<EnumSet<Foo>>::parse("").is_err());
+    }
+
+    #[test]
+    fn test_from_form_repeated() {
+        assert_eq!(set(&[A, C]), Form::parse("features=A&features=C").unwrap());
+        assert_eq!(set(&[A, B, C]), Form::parse("features=A,B&features=C").unwrap());
+        let errors = Form::<EnumSet<Foo>>::parse("features=A&features=D").unwrap_err();
+        assert_eq!(1, errors.len());
+    }
+
+    #[test]
+    fn test_from_param() {
+        assert_eq!(set(&[B]), EnumSet::from_param("B").unwrap());
+        assert!(EnumSet::<Foo>::from_param("B,E").is_err());
+    }
+}
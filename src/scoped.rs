@@ -0,0 +1,142 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use {bit, CLike, EnumSet};
+
+/// A guard returned by `EnumSet::scoped_insert`.
+///
+/// Removes the element from the set when dropped, unless it was already
+/// present when the guard was created.
+pub struct ScopedFlag<'a, E: 'a> {
+    set: &'a mut EnumSet<E>,
+    bit: u32,
+}
+
+impl<'a, E> ScopedFlag<'a, E> {
+    /// Returns `true` if the element was inserted by this guard, and so
+    /// will be removed when it is dropped.
+    pub fn inserted(&self) -> bool {
+        self.bit != 0
+    }
+}
+
+impl<'a, E> Deref for ScopedFlag<'a, E> {
+    type Target = EnumSet<E>;
+
+    fn deref(&self) -> &EnumSet<E> {
+        self.set
+    }
+}
+
+impl<'a, E> Drop for ScopedFlag<'a, E> {
+    fn drop(&mut self) {
+        self.set.bits &= !self.bit;
+    }
+}
+
+/// A guard returned by `EnumSet::scoped_insert_cell`.
+///
+/// Like `ScopedFlag`, but borrows the set through a `Cell` so that the set
+/// can still be read and modified while the guard is alive.
+pub struct ScopedCellFlag<'a, E: 'a> {
+    cell: &'a Cell<EnumSet<E>>,
+    bit: u32,
+}
+
+impl<'a, E> ScopedCellFlag<'a, E> {
+    /// Returns `true` if the element was inserted by this guard, and so
+    /// will be removed when it is dropped.
+    pub fn inserted(&self) -> bool {
+        self.bit != 0
+    }
+}
+
+impl<'a, E> Drop for ScopedCellFlag<'a, E> {
+    fn drop(&mut self) {
+        let empty = EnumSet { bits: 0, phantom: PhantomData };
+        let mut set = self.cell.replace(empty);
+        set.bits &= !self.bit;
+        self.cell.set(set);
+    }
+}
+
+impl<E: CLike> EnumSet<E> {
+    /// Inserts `value` and returns a guard which removes it again when
+    /// dropped.
+    ///
+    /// If `value` was already present, it is left in the set.
+    pub fn scoped_insert(&mut self, value: E) -> ScopedFlag<'_, E> {
+        let bit = bit(&value) & !self.bits;
+        self.bits |= bit;
+        ScopedFlag { set: self, bit }
+    }
+
+    /// Inserts `value` into the set in `cell` and returns a guard which
+    /// removes it again when dropped.
+    ///
+    /// If `value` was already present, it is left in the set.
+    pub fn scoped_insert_cell(cell: &Cell<Self>, value: E) -> ScopedCellFlag<'_, E> {
+        let mut set = cell.replace(EnumSet::new_with_bits(0));
+        let bit = bit(&value) & !set.bits;
+        set.bits |= bit;
+        cell.set(set);
+        ScopedCellFlag { cell, bit }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_scoped_insert() {
+        let mut flags = set(&[A]);
+        {
+            let guard = flags.scoped_insert(B);
+            assert!(guard.inserted());
+            assert_eq!(set(&[A, B]), *guard);
+        }
+        assert_eq!(set(&[A]), flags);
+        {
+            let guard = flags.scoped_insert(A);
+            assert!(!guard.inserted());
+        }
+        assert_eq!(set(&[A]), flags);
+    }
+
+    #[test]
+    fn test_scoped_insert_cell() {
+        fn process(flags: &Cell<EnumSet<Foo>>, depth: u32) -> u32 {
+            let guard = EnumSet::scoped_insert_cell(flags, B);
+            if !guard.inserted() {
+                return depth;
+            }
+            process(flags, depth + 1)
+        }
+
+        let flags = Cell::new(set(&[C]));
+        assert_eq!(1, process(&flags, 0));
+        assert_eq!(set(&[C]), flags.get());
+
+        let guard = EnumSet::scoped_insert_cell(&flags, A);
+        flags.set(flags.get() | set(&[B]));
+        drop(guard);
+        assert_eq!(set(&[B, C]), flags.get());
+    }
+}
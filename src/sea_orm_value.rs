@@ -0,0 +1,94 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Use of sets as SeaORM column values.
+//!
+//! A set is stored as its bits in a `BIGINT` column, which every supported
+//! database can represent without loss.
+
+use sea_orm::sea_query::{ArrayType, ColumnType, Nullable, Value, ValueType, ValueTypeErr};
+use sea_orm::{ColIdx, DbErr, QueryResult, TryGetError, TryGetable};
+
+use {universe, EnumSet, Finite};
+
+fn from_bits<E: Finite>(bits: i64) -> Option<EnumSet<E>> {
+    if bits >= 0 && bits & !(universe::<E>() as i64) == 0 {
+        Some(EnumSet::new_with_bits(bits as u32))
+    } else {
+        None
+    }
+}
+
+impl<E: Finite> From<EnumSet<E>> for Value {
+    fn from(set: EnumSet<E>) -> Value {
+        Value::BigInt(Some(set.bits as i64))
+    }
+}
+
+impl<E: Finite> Nullable for EnumSet<E> {
+    fn null() -> Value {
+        Value::BigInt(None)
+    }
+}
+
+impl<E: Finite> ValueType for EnumSet<E> {
+    fn try_from(v: Value) -> Result<Self, ValueTypeErr> {
+        match v {
+            Value::BigInt(Some(bits)) => from_bits(bits).ok_or(ValueTypeErr),
+            _ => Err(ValueTypeErr),
+        }
+    }
+
+    fn type_name() -> String {
+        "EnumSet".to_owned()
+    }
+
+    fn array_type() -> ArrayType {
+        ArrayType::BigInt
+    }
+
+    fn column_type() -> ColumnType {
+        ColumnType::BigInteger
+    }
+}
+
+impl<E: Finite> TryGetable for EnumSet<E> {
+    fn try_get_by<I: ColIdx>(res: &QueryResult, index: I) -> Result<Self, TryGetError> {
+        let bits = i64::try_get_by(res, index)?;
+        from_bits(bits).ok_or_else(|| {
+            TryGetError::DbErr(DbErr::Type(format!("{} is not a valid EnumSet", bits)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::sea_query::{Value, ValueType};
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_value_round_trip() {
+        let value: Value = set(&[A, C]).into();
+        assert_eq!(Value::BigInt(Some(0b101)), value);
+        assert_eq!(Ok(set(&[A, C])), <EnumSet<Foo> as ValueType>::try_from(value).map_err(|_| ()));
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(<EnumSet<Foo> as ValueType>::try_from(Value::BigInt(Some(0b1000))).is_err());
+        assert!(<EnumSet<Foo> as ValueType>::try_from(Value::BigInt(Some(-1))).is_err());
+        assert!(<EnumSet<Foo> as ValueType>::try_from(Value::BigInt(None)).is_err());
+        assert!(<EnumSet<Foo> as ValueType>::try_from(Value::Int(Some(1))).is_err());
+    }
+}
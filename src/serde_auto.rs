@@ -0,0 +1,151 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serialization of sets which depends on the format.
+//!
+//! Formats meant to be read by people, as reported by
+//! `Serializer::is_human_readable`, get a sequence of variant names as with
+//! `serde_names`. Other formats get the bits of the set as a `u32`, as with
+//! `serde_bits`.
+//!
+//! This is the representation used by the `Serialize` and `Deserialize`
+//! implementations of `EnumSet`. To use the same representation with every
+//! format instead, use `#[serde(with = "enum_set::serde_names")]` or
+//! `#[serde(with = "enum_set::serde_bits")]`.
+
+use std::fmt;
+
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use {serde_bits, serde_names, EnumSet, Finite};
+
+/// Serializes `set` as a sequence of names for human-readable formats, and
+/// as its bits otherwise.
+pub fn serialize<E, S>(set: &EnumSet<E>, serializer: S) -> Result<S::Ok, S::Error>
+    where E: Finite + fmt::Debug,
+          S: Serializer
+{
+    if serializer.is_human_readable() {
+        serde_names::serialize(set, serializer)
+    } else {
+        serde_bits::serialize(set, serializer)
+    }
+}
+
+/// Deserializes a set serialized by `serialize` with the same format.
+pub fn deserialize<'de, E, D>(deserializer: D) -> Result<EnumSet<E>, D::Error>
+    where E: Finite + fmt::Debug,
+          D: Deserializer<'de>
+{
+    if deserializer.is_human_readable() {
+        serde_names::deserialize(deserializer)
+    } else {
+        serde_bits::deserialize(deserializer)
+    }
+}
+
+impl<E: Finite + fmt::Debug> Serialize for EnumSet<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de, E: Finite + fmt::Debug> Deserialize<'de> for EnumSet<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "bson")]
+    use bson;
+    use serde::de::{self, Deserializer, Visitor};
+    use serde_json;
+
+    use tests::{Foo, Mode};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Config {
+        features: EnumSet<Foo>,
+    }
+
+    /// A deserializer of a single `u32` for a format which is not
+    /// human-readable.
+    struct Compact(u32);
+
+    impl<'de> Deserializer<'de> for Compact {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.visit_u32(self.0)
+        }
+
+        fn is_human_readable(&self) -> bool {
+            false
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct enum identifier ignored_any
+        }
+    }
+
+    #[test]
+    fn test_human_readable() {
+        let set: EnumSet<Foo> = [A, C].iter().collect();
+        let mut out = Vec::new();
+        super::serialize(&set, &mut serde_json::Serializer::new(&mut out)).unwrap();
+        assert_eq!(r#"["A","C"]"#, String::from_utf8(out).unwrap());
+
+        let mut de = serde_json::Deserializer::from_str(r#"["A","C"]"#);
+        assert_eq!(set, super::deserialize(&mut de).unwrap());
+        let mut de = serde_json::Deserializer::from_str(r#"["Alpha","Gamma"]"#);
+        assert_eq!(set, super::deserialize(&mut de).unwrap());
+    }
+
+    #[test]
+    fn test_compact() {
+        let set: EnumSet<Foo> = [A, C].iter().collect();
+        assert_eq!(set, super::deserialize(Compact(5)).unwrap());
+        assert!(super::deserialize::<Foo, _>(Compact(8)).is_err());
+    }
+
+    #[test]
+    fn test_derive_human_readable() {
+        let config = Config { features: [A, C].iter().collect() };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(r#"{"features":["A","C"]}"#, json);
+        assert_eq!(config, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn test_name_case() {
+        let set: EnumSet<Mode> = [Mode::ReadOnly, Mode::HTTPProxy].iter().collect();
+        assert_eq!(r#"["read-only","http-proxy"]"#, serde_json::to_string(&set).unwrap());
+        assert_eq!(set, serde_json::from_str(r#"["http-proxy","READ-ONLY"]"#).unwrap());
+        assert_eq!(set, super::deserialize(Compact(0b101)).unwrap());
+    }
+
+    #[cfg(feature = "bson")]
+    #[test]
+    fn test_derive_compact() {
+        let config = Config { features: [A, C].iter().collect() };
+        let bytes = bson::serialize_to_vec(&config).unwrap();
+        let document = bson::Document::from_reader(&bytes[..]).unwrap();
+        assert_eq!(5, document.get_i64("features").unwrap());
+        assert_eq!(config, bson::deserialize_from_slice(&bytes).unwrap());
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serialization of sets as their bits.
+//!
+//! A set is serialized as a `u32` holding its bits, which is the
+//! representation expected by formats describing sets as bit fields, such
+//! as D-Bus. Deserialization fails if bits are set which do not correspond
+//! to a variant.
+//!
+//! These functions are meant to be used with
+//! `#[serde(with = "enum_set::serde_bits")]`.
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::Serializer;
+
+use {universe, CLike, EnumSet, Finite};
+
+/// Serializes the bits of `set` as a `u32`.
+pub fn serialize<E, S>(set: &EnumSet<E>, serializer: S) -> Result<S::Ok, S::Error>
+    where E: CLike,
+          S: Serializer
+{
+    serializer.serialize_u32(set.bits)
+}
+
+/// Deserializes a set from a `u32` holding its bits.
+pub fn deserialize<'de, E, D>(deserializer: D) -> Result<EnumSet<E>, D::Error>
+    where E: Finite,
+          D: Deserializer<'de>
+{
+    let bits = u32::deserialize(deserializer)?;
+    if bits & !universe::<E>() == 0 {
+        Ok(EnumSet::new_with_bits(bits))
+    } else {
+        Err(de::Error::invalid_value(de::Unexpected::Unsigned(bits.into()), &"the bits of a set"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use tests::Foo;
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_round_trip() {
+        let set: EnumSet<Foo> = [A, C].iter().collect();
+        let mut out = Vec::new();
+        super::serialize(&set, &mut serde_json::Serializer::new(&mut out)).unwrap();
+        assert_eq!("5", String::from_utf8(out).unwrap());
+
+        let mut de = serde_json::Deserializer::from_str("5");
+        assert_eq!(set, super::deserialize(&mut de).unwrap());
+        let mut de = serde_json::Deserializer::from_str("8");
+        assert!(super::deserialize::<Foo, _>(&mut de).is_err());
+    }
+}
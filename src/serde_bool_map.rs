@@ -0,0 +1,132 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serialization of sets as maps from variant names to booleans.
+//!
+//! Every variant is serialized, with the value `true` if the set contains
+//! it. When deserializing, variants which are absent from the map are not
+//! in the set. Names are the `Debug` representations of the variants in
+//! `Finite::NAME_CASE`.
+//!
+//! These functions are meant to be used with `#[serde(with = "...")]`:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Settings {
+//!     #[serde(with = "enum_set::serde_bool_map")]
+//!     notifications: EnumSet<Notification>,
+//! }
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::ser::{SerializeMap, Serializer};
+
+use {names, EnumSet, Finite};
+
+/// Serializes `set` as a map from every variant name to whether `set`
+/// contains the variant.
+pub fn serialize<E, S>(set: &EnumSet<E>, serializer: S) -> Result<S::Ok, S::Error>
+    where E: Finite + fmt::Debug,
+          S: Serializer
+{
+    let mut map = serializer.serialize_map(Some(E::VARIANT_COUNT as usize))?;
+    for (variant, name) in names::variants::<E>() {
+        map.serialize_entry(&name, &set.contains(&variant))?;
+    }
+    map.end()
+}
+
+/// Deserializes a set from a map from variant names to booleans.
+///
+/// Fails if the map contains a name which is not a variant.
+pub fn deserialize<'de, E, D>(deserializer: D) -> Result<EnumSet<E>, D::Error>
+    where E: Finite + fmt::Debug,
+          D: Deserializer<'de>
+{
+    deserializer.deserialize_map(BoolMapVisitor(PhantomData))
+}
+
+struct BoolMapVisitor<E>(PhantomData<E>);
+
+impl<'de, E: Finite + fmt::Debug> Visitor<'de> for BoolMapVisitor<E> {
+    type Value = EnumSet<E>;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a map from variant names to booleans")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<EnumSet<E>, A::Error> {
+        let mut set = EnumSet::new();
+        while let Some((name, value)) = map.next_entry::<String, bool>()? {
+            match names::from_name::<E>(&name) {
+                Some(variant) => {
+                    if value {
+                        set.insert(variant);
+                    } else {
+                        set.remove(&variant);
+                    }
+                }
+                None => return Err(de::Error::custom(format_args!("unknown variant `{}`", name))),
+            }
+        }
+        Ok(set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use tests::{set, Foo, Mode};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    fn to_json(set: &EnumSet<Foo>) -> String {
+        let mut out = Vec::new();
+        super::serialize(set, &mut serde_json::Serializer::new(&mut out)).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn from_json(json: &str) -> Result<EnumSet<Foo>, serde_json::Error> {
+        super::deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn test_serialize() {
+        assert_eq!(r#"{"A":true,"B":false,"C":true}"#, to_json(&set(&[A, C])));
+        assert_eq!(r#"{"A":false,"B":false,"C":false}"#, to_json(&set(&[])));
+    }
+
+    #[test]
+    fn test_deserialize() {
+        assert_eq!(set(&[A, C]), from_json(&to_json(&set(&[A, C]))).unwrap());
+        assert_eq!(set(&[B]), from_json(r#"{"B":true,"C":false}"#).unwrap());
+        assert_eq!(set(&[A]), from_json(r#"{"Alpha":true,"Gamma":false}"#).unwrap());
+        assert_eq!(set(&[]), from_json("{}").unwrap());
+        assert!(from_json(r#"{"D":true}"#).is_err());
+        assert!(from_json(r#"{"A":1}"#).is_err());
+    }
+
+    #[test]
+    fn test_name_case() {
+        let set: EnumSet<Mode> = [Mode::HTTPProxy].iter().collect();
+        let mut out = Vec::new();
+        super::serialize(&set, &mut serde_json::Serializer::new(&mut out)).unwrap();
+        assert_eq!(r#"{"read-only":false,"read-write":false,"http-proxy":true}"#,
+                   String::from_utf8(out).unwrap());
+
+        let mut de = serde_json::Deserializer::from_str(r#"{"Http-Proxy":true,"Read-Only":false}"#);
+        assert_eq!(set, super::deserialize(&mut de).unwrap());
+    }
+}
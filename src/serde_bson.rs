@@ -0,0 +1,160 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions between sets and BSON values.
+//!
+//! A set converts to a BSON array of variant names, where names are the
+//! `Debug` representations of the variants in `Finite::NAME_CASE`. A set can
+//! be converted back from such an array, or from an integer holding its
+//! bits.
+//!
+//! The `serialize` and `deserialize` functions use the same representation
+//! and are meant to be used with `#[serde(with = "enum_set::serde_bson")]`.
+
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+
+use bson::Bson;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use {names, universe, EnumSet, Finite};
+
+/// An error returned when converting a BSON value to an `EnumSet`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FromBsonError {
+    /// The value is neither an array nor an integer.
+    UnexpectedType,
+    /// An element of the array is not a string.
+    NotAString,
+    /// An element of the array is not the name of a variant.
+    UnknownName(String),
+    /// The integer has bits set which do not correspond to a variant.
+    InvalidBits(i64),
+}
+
+impl fmt::Display for FromBsonError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FromBsonError::UnexpectedType => fmt.write_str("expected an array or an integer"),
+            FromBsonError::NotAString => fmt.write_str("expected an array of strings"),
+            FromBsonError::UnknownName(ref name) => write!(fmt, "unknown variant `{}`", name),
+            FromBsonError::InvalidBits(bits) => write!(fmt, "invalid bits {:#x}", bits),
+        }
+    }
+}
+
+impl error::Error for FromBsonError {}
+
+fn to_bson<E: Finite + fmt::Debug>(set: &EnumSet<E>) -> Bson {
+    Bson::Array(set.iter().map(|variant| Bson::String(names::name(&variant))).collect())
+}
+
+impl<E: Finite + fmt::Debug> From<EnumSet<E>> for Bson {
+    fn from(set: EnumSet<E>) -> Bson {
+        to_bson(&set)
+    }
+}
+
+impl<E: Finite + fmt::Debug> TryFrom<Bson> for EnumSet<E> {
+    type Error = FromBsonError;
+
+    fn try_from(value: Bson) -> Result<Self, FromBsonError> {
+        let bits = match value {
+            Bson::Array(elements) => {
+                let elements = elements.into_iter().map(|element| match element {
+                    Bson::String(name) => Ok(name),
+                    _ => Err(FromBsonError::NotAString),
+                });
+                return names::collect(elements, |name| Err(FromBsonError::UnknownName(name)));
+            }
+            Bson::Int32(bits) => i64::from(bits),
+            Bson::Int64(bits) => bits,
+            _ => return Err(FromBsonError::UnexpectedType),
+        };
+        if bits >= 0 && bits & !i64::from(universe::<E>()) == 0 {
+            Ok(EnumSet::new_with_bits(bits as u32))
+        } else {
+            Err(FromBsonError::InvalidBits(bits))
+        }
+    }
+}
+
+/// Serializes `set` as its BSON representation.
+pub fn serialize<E, S>(set: &EnumSet<E>, serializer: S) -> Result<S::Ok, S::Error>
+    where E: Finite + fmt::Debug,
+          S: Serializer
+{
+    to_bson(set).serialize(serializer)
+}
+
+/// Deserializes a set from its BSON representation.
+pub fn deserialize<'de, E, D>(deserializer: D) -> Result<EnumSet<E>, D::Error>
+    where E: Finite + fmt::Debug,
+          D: Deserializer<'de>
+{
+    let value = Bson::deserialize(deserializer)?;
+    EnumSet::try_from(value).map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use bson::Bson;
+    use serde_json;
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::FromBsonError;
+
+    fn names(names: &[&str]) -> Bson {
+        Bson::Array(names.iter().map(|&name| Bson::String(name.to_owned())).collect())
+    }
+
+    #[test]
+    fn test_to_bson() {
+        assert_eq!(names(&["A", "C"]), Bson::from(set(&[A, C])));
+        assert_eq!(names(&[]), Bson::from(set(&[])));
+    }
+
+    #[test]
+    fn test_from_bson() {
+        assert_eq!(Ok(set(&[A, C])), EnumSet::try_from(names(&["C", "A"])));
+        assert_eq!(Ok(set(&[B])), EnumSet::try_from(Bson::Int32(0b10)));
+        assert_eq!(Ok(set(&[A, B])), EnumSet::try_from(Bson::Int64(0b11)));
+
+        assert_eq!(Err(FromBsonError::UnknownName("D".to_owned())),
+                   EnumSet::<Foo>::try_from(names(&["A", "D"])));
+        assert_eq!(Err(FromBsonError::NotAString),
+                   EnumSet::<Foo>::try_from(Bson::Array(vec![Bson::Int32(0)])));
+        assert_eq!(Err(FromBsonError::InvalidBits(0b1000)),
+                   EnumSet::<Foo>::try_from(Bson::Int32(0b1000)));
+        assert_eq!(Err(FromBsonError::InvalidBits(-1)),
+                   EnumSet::<Foo>::try_from(Bson::Int64(-1)));
+        assert_eq!(Err(FromBsonError::UnexpectedType),
+                   EnumSet::<Foo>::try_from(Bson::Boolean(true)));
+    }
+
+    #[test]
+    fn test_serde() {
+        let mut out = Vec::new();
+        super::serialize(&set(&[A, B]), &mut serde_json::Serializer::new(&mut out)).unwrap();
+        assert_eq!(r#"["A","B"]"#, String::from_utf8(out).unwrap());
+
+        let mut de = serde_json::Deserializer::from_str(r#"["B"]"#);
+        assert_eq!(set(&[B]), super::deserialize(&mut de).unwrap());
+        let mut de = serde_json::Deserializer::from_str(r#"["E"]"#);
+        assert!(super::deserialize::<Foo, _>(&mut de).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serialization of sets which accepts both bits and names.
+//!
+//! A set is serialized as a sequence of variant names, as with
+//! `serde_names`. Deserialization also accepts an integer holding the bits
+//! of the set, as written by `serde_bits`, which helps when migrating data
+//! from one representation to the other.
+//!
+//! Telling the two apart requires `Deserializer::deserialize_any`, so this
+//! only works with self-describing formats such as JSON.
+//!
+//! These functions are meant to be used with
+//! `#[serde(with = "enum_set::serde_lenient")]`.
+
+use std::fmt;
+use std::iter;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::Serializer;
+
+use {names, serde_names, universe, EnumSet, Finite};
+
+/// Serializes `set` as a sequence of the names of its elements.
+pub fn serialize<E, S>(set: &EnumSet<E>, serializer: S) -> Result<S::Ok, S::Error>
+    where E: Finite + fmt::Debug,
+          S: Serializer
+{
+    serde_names::serialize(set, serializer)
+}
+
+/// Deserializes a set from either an integer holding its bits or a
+/// sequence of variant names.
+pub fn deserialize<'de, E, D>(deserializer: D) -> Result<EnumSet<E>, D::Error>
+    where E: Finite + fmt::Debug,
+          D: Deserializer<'de>
+{
+    deserializer.deserialize_any(LenientVisitor(PhantomData))
+}
+
+struct LenientVisitor<E>(PhantomData<E>);
+
+impl<'de, E: Finite + fmt::Debug> Visitor<'de> for LenientVisitor<E> {
+    type Value = EnumSet<E>;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("the bits of a set or a sequence of variant names")
+    }
+
+    fn visit_u64<Err: de::Error>(self, value: u64) -> Result<EnumSet<E>, Err> {
+        if value & !u64::from(universe::<E>()) == 0 {
+            Ok(EnumSet::new_with_bits(value as u32))
+        } else {
+            Err(de::Error::invalid_value(de::Unexpected::Unsigned(value), &self))
+        }
+    }
+
+    fn visit_i64<Err: de::Error>(self, value: i64) -> Result<EnumSet<E>, Err> {
+        if value >= 0 {
+            self.visit_u64(value as u64)
+        } else {
+            Err(de::Error::invalid_value(de::Unexpected::Signed(value), &self))
+        }
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<EnumSet<E>, A::Error> {
+        let elements = iter::from_fn(|| seq.next_element::<String>().transpose());
+        names::collect(elements, |name| {
+            Err(de::Error::custom(format_args!("unknown variant `{}`", name)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use tests::{Foo, Mode};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    fn from_json(json: &str) -> Result<EnumSet<Foo>, serde_json::Error> {
+        super::deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn test_serialize() {
+        let set: EnumSet<Foo> = [A, C].iter().collect();
+        let mut out = Vec::new();
+        super::serialize(&set, &mut serde_json::Serializer::new(&mut out)).unwrap();
+        assert_eq!(r#"["A","C"]"#, String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let set: EnumSet<Foo> = [A, C].iter().collect();
+        assert_eq!(set, from_json("5").unwrap());
+        assert_eq!(set, from_json(r#"["A","C"]"#).unwrap());
+        assert_eq!(set, from_json(r#"["Alpha","Gamma"]"#).unwrap());
+        assert!(from_json("0").unwrap().is_empty());
+        assert!(from_json("8").is_err());
+        assert!(from_json("-1").is_err());
+        assert!(from_json(r#"["D"]"#).is_err());
+        assert!(from_json(r#""A""#).is_err());
+    }
+
+    #[test]
+    fn test_name_case() {
+        let set: EnumSet<Mode> = [Mode::ReadWrite].iter().collect();
+        let mut out = Vec::new();
+        super::serialize(&set, &mut serde_json::Serializer::new(&mut out)).unwrap();
+        assert_eq!(r#"["read-write"]"#, String::from_utf8(out).unwrap());
+
+        let mut de = serde_json::Deserializer::from_str(r#"["READ-WRITE"]"#);
+        assert_eq!(set, super::deserialize(&mut de).unwrap());
+        let mut de = serde_json::Deserializer::from_str("2");
+        assert_eq!(set, super::deserialize(&mut de).unwrap());
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serialization of sets as sequences of variant names.
+//!
+//! Names are the `Debug` representations of the variants in
+//! `Finite::NAME_CASE`, and are serialized in the order of the variants. Deserialization fails if a name
+//! is not a variant.
+//!
+//! These functions are meant to be used with
+//! `#[serde(with = "enum_set::serde_names")]`.
+
+use std::fmt;
+use std::iter;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeSeq, Serializer};
+
+use {names, EnumSet, Finite};
+
+/// Serializes `set` as a sequence of the names of its elements.
+pub fn serialize<E, S>(set: &EnumSet<E>, serializer: S) -> Result<S::Ok, S::Error>
+    where E: Finite + fmt::Debug,
+          S: Serializer
+{
+    let mut seq = serializer.serialize_seq(Some(set.len()))?;
+    for element in set.iter() {
+        seq.serialize_element(&names::name(&element))?;
+    }
+    seq.end()
+}
+
+/// Deserializes a set from a sequence of variant names.
+pub fn deserialize<'de, E, D>(deserializer: D) -> Result<EnumSet<E>, D::Error>
+    where E: Finite + fmt::Debug,
+          D: Deserializer<'de>
+{
+    deserializer.deserialize_seq(NamesVisitor(PhantomData))
+}
+
+struct NamesVisitor<E>(PhantomData<E>);
+
+impl<'de, E: Finite + fmt::Debug> Visitor<'de> for NamesVisitor<E> {
+    type Value = EnumSet<E>;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a sequence of variant names")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<EnumSet<E>, A::Error> {
+        let elements = iter::from_fn(|| seq.next_element::<String>().transpose());
+        names::collect(elements, |name| {
+            Err(de::Error::custom(format_args!("unknown variant `{}`", name)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use tests::{Foo, Mode};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    fn from_json(json: &str) -> Result<EnumSet<Foo>, serde_json::Error> {
+        super::deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let set: EnumSet<Foo> = [C, A].iter().collect();
+        let mut out = Vec::new();
+        super::serialize(&set, &mut serde_json::Serializer::new(&mut out)).unwrap();
+        assert_eq!(r#"["A","C"]"#, String::from_utf8(out).unwrap());
+
+        assert_eq!(set, from_json(r#"["C","A","C"]"#).unwrap());
+        assert_eq!(set, from_json(r#"["Gamma","Alpha"]"#).unwrap());
+        assert!(from_json("[]").unwrap().is_empty());
+        assert!(from_json(r#"["D"]"#).is_err());
+        assert!(from_json("5").is_err());
+    }
+
+    #[test]
+    fn test_name_case() {
+        let set: EnumSet<Mode> = [Mode::ReadOnly, Mode::HTTPProxy].iter().collect();
+        let mut out = Vec::new();
+        super::serialize(&set, &mut serde_json::Serializer::new(&mut out)).unwrap();
+        assert_eq!(r#"["read-only","http-proxy"]"#, String::from_utf8(out).unwrap());
+
+        let mut de = serde_json::Deserializer::from_str(r#"["HTTP-Proxy","read-only"]"#);
+        assert_eq!(set, super::deserialize(&mut de).unwrap());
+        let mut de = serde_json::Deserializer::from_str(r#"["ReadOnly"]"#);
+        assert!(super::deserialize::<Mode, _>(&mut de).is_err());
+    }
+}
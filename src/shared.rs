@@ -0,0 +1,189 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::borrow;
+use std::fmt;
+use std::future::Future;
+
+use tokio::sync::watch::{self, error::RecvError};
+
+use {bit, CLike, EnumSet};
+
+/// A set which broadcasts its changes over a `tokio::sync::watch` channel.
+///
+/// Tasks holding a `FlagsReceiver` obtained from `subscribe` can await any
+/// change of the set, or wait until it contains given elements. Receivers
+/// are only notified of mutations which change the set.
+pub struct SharedFlags<E> {
+    sender: watch::Sender<EnumSet<E>>,
+}
+
+impl<E: CLike> SharedFlags<E> {
+    /// Returns a `SharedFlags` holding `initial`.
+    pub fn new(initial: EnumSet<E>) -> Self {
+        SharedFlags { sender: watch::Sender::new(initial) }
+    }
+
+    /// Returns the current value of the set.
+    pub fn get(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.sender.borrow().bits)
+    }
+
+    /// Returns a receiver for changes of the set.
+    pub fn subscribe(&self) -> FlagsReceiver<E> {
+        FlagsReceiver { receiver: self.sender.subscribe() }
+    }
+
+    /// Adds the given value to the set.
+    ///
+    /// Returns `true` if the value was not already present in the set.
+    pub fn insert(&self, value: E) -> bool {
+        let value = bit(&value);
+        self.modify(|bits| bits | value)
+    }
+
+    /// Removes a value from the set.
+    ///
+    /// Returns `true` if the value was present in the set.
+    pub fn remove<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        let value = bit(value.borrow());
+        self.modify(|bits| bits & !value)
+    }
+
+    /// Replaces the set with `set`.
+    ///
+    /// Returns `true` if the set changed.
+    pub fn replace(&self, set: EnumSet<E>) -> bool {
+        self.modify(|_| set.bits)
+    }
+
+    fn modify<F: FnOnce(u32) -> u32>(&self, f: F) -> bool {
+        self.sender.send_if_modified(|set| {
+            let bits = f(set.bits);
+            let modified = bits != set.bits;
+            set.bits = bits;
+            modified
+        })
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for SharedFlags<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_tuple("SharedFlags").field(&self.get()).finish()
+    }
+}
+
+/// A receiver for changes of a `SharedFlags`.
+///
+/// The methods fail with `RecvError` once the `SharedFlags` is dropped.
+pub struct FlagsReceiver<E> {
+    receiver: watch::Receiver<EnumSet<E>>,
+}
+
+impl<E: CLike> FlagsReceiver<E> {
+    /// Returns the current value of the set.
+    pub fn get(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.receiver.borrow().bits)
+    }
+
+    /// Waits for a change of the set which this receiver has not seen yet.
+    ///
+    /// The new value can be read with `get` once the future completes.
+    pub fn changed(&mut self) -> impl Future<Output = Result<(), RecvError>> + '_ {
+        self.receiver.changed()
+    }
+
+    /// Waits until the set contains every element of `subset`, and returns
+    /// a reference to its value at that point.
+    ///
+    /// Completes immediately if the set already contains `subset`. The
+    /// reference holds a read lock on the set, so it should not be kept
+    /// across an `.await`.
+    pub fn wait_for(&mut self, subset: EnumSet<E>)
+                    -> impl Future<Output = Result<watch::Ref<'_, EnumSet<E>>, RecvError>> + '_ {
+        self.receiver.wait_for(move |set| set.is_superset(&subset))
+    }
+}
+
+impl<E> Clone for FlagsReceiver<E> {
+    fn clone(&self) -> Self {
+        FlagsReceiver { receiver: self.receiver.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::pin::{pin, Pin};
+    use std::task::{Context, Poll, Waker};
+
+    use tests::set;
+    use tests::Foo::*;
+
+    use super::SharedFlags;
+
+    fn poll<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+        future.poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn test_mutations() {
+        let flags = SharedFlags::new(set(&[A]));
+        assert!(flags.insert(B));
+        assert!(!flags.insert(B));
+        assert!(flags.remove(A));
+        assert!(!flags.remove(A));
+        assert!(flags.replace(set(&[C])));
+        assert_eq!(set(&[C]), flags.get());
+    }
+
+    #[test]
+    fn test_changed() {
+        let flags = SharedFlags::new(set(&[]));
+        let mut receiver = flags.subscribe();
+        {
+            let mut changed = pin!(receiver.changed());
+            assert!(poll(changed.as_mut()).is_pending());
+            flags.insert(A);
+            assert!(matches!(poll(changed.as_mut()), Poll::Ready(Ok(()))));
+        }
+        assert_eq!(set(&[A]), receiver.get());
+
+        let mut changed = pin!(receiver.changed());
+        flags.insert(A);
+        assert!(poll(changed.as_mut()).is_pending());
+        drop(flags);
+        assert!(matches!(poll(changed.as_mut()), Poll::Ready(Err(_))));
+    }
+
+    #[test]
+    fn test_wait_for() {
+        let flags = SharedFlags::new(set(&[A]));
+        let mut receiver = flags.subscribe();
+        {
+            let mut ready = pin!(receiver.wait_for(set(&[A, C])));
+            assert!(poll(ready.as_mut()).is_pending());
+            flags.insert(B);
+            assert!(poll(ready.as_mut()).is_pending());
+            flags.insert(C);
+            match poll(ready.as_mut()) {
+                Poll::Ready(Ok(value)) => assert_eq!(set(&[A, B, C]), *value),
+                _ => panic!("expected the set to be ready"),
+            }
+        }
+
+        let mut receiver = flags.subscribe();
+        let ready = pin!(receiver.wait_for(set(&[B])));
+        match poll(ready) {
+            Poll::Ready(Ok(value)) => assert_eq!(set(&[A, B, C]), *value),
+            _ => panic!("expected the set to be ready"),
+        };
+    }
+}
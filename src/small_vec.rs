@@ -0,0 +1,58 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use smallvec::SmallVec;
+
+use {CLike, EnumSet};
+
+impl<E: CLike> EnumSet<E> {
+    /// Returns the set's elements in order, stored inline if there are no
+    /// more than `N` of them.
+    pub fn to_smallvec<const N: usize>(&self) -> SmallVec<[E; N]> {
+        self.iter().collect()
+    }
+}
+
+impl<E: CLike, const N: usize> From<EnumSet<E>> for SmallVec<[E; N]> {
+    fn from(set: EnumSet<E>) -> Self {
+        set.to_smallvec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use smallvec::SmallVec;
+
+    use tests::Foo;
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_to_smallvec() {
+        let set: EnumSet<Foo> = [C, A].iter().collect();
+        let elements = set.to_smallvec::<4>();
+        assert!(!elements.spilled());
+        assert_eq!(&[A, C], &elements[..]);
+
+        let elements = set.to_smallvec::<1>();
+        assert!(elements.spilled());
+        assert_eq!(&[A, C], &elements[..]);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let set: EnumSet<Foo> = [B, C].iter().collect();
+        let elements: SmallVec<[Foo; 3]> = set.into();
+        assert_eq!(&[B, C], &elements[..]);
+        assert_eq!(set, elements.iter().collect());
+        assert_eq!(set, elements.into_iter().collect());
+    }
+}
@@ -0,0 +1,229 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sets of enums with large or scattered discriminants.
+
+use std::borrow;
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+
+use CLike;
+
+/// A `CLike` enum whose `to_u32` values are listed in a table.
+///
+/// `SparseEnumSet` stores the position of a variant's value in `CODES`
+/// rather than the value itself, so the values can be arbitrarily large as
+/// long as there are no more than 32 of them.
+///
+/// # Safety
+///
+/// `CODES` must be sorted in ascending order, hold no more than 32 values,
+/// and contain the `to_u32` value of every variant. `CLike::from_u32` must
+/// be safe to call with every value in `CODES`.
+pub unsafe trait SparseCLike: CLike {
+    /// The `to_u32` values of every variant, in ascending order.
+    const CODES: &'static [u32];
+}
+
+/// Returns the position of `value` in `E::CODES`.
+fn index<E: SparseCLike>(value: &E) -> u32 {
+    match E::CODES.binary_search(&value.to_u32()) {
+        Ok(index) => index as u32,
+        Err(_) => panic!("value {} is not listed in SparseCLike::CODES", value.to_u32()),
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A set of `SparseCLike` enums, stored as a bit mask over their positions
+/// in `SparseCLike::CODES`.
+pub struct SparseEnumSet<E> {
+    bits: u32,
+    phantom: PhantomData<E>,
+}
+
+impl<E: SparseCLike> SparseEnumSet<E> {
+    /// Returns an empty `SparseEnumSet`.
+    pub fn new() -> Self {
+        SparseEnumSet { bits: 0, phantom: PhantomData }
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    /// Checks if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Adds an enum to the set, returning `true` if it was not already
+    /// present.
+    pub fn insert(&mut self, value: E) -> bool {
+        let bit = 1 << index(&value);
+        let result = self.bits & bit == 0;
+        self.bits |= bit;
+        result
+    }
+
+    /// Removes an enum from the set, returning `true` if it was present.
+    pub fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        let bit = 1 << index(value.borrow());
+        let result = self.bits & bit != 0;
+        self.bits &= !bit;
+        result
+    }
+
+    /// Returns `true` if the set contains the given enum.
+    pub fn contains<V: borrow::Borrow<E>>(&self, value: V) -> bool {
+        self.bits & (1 << index(value.borrow())) != 0
+    }
+
+    /// Returns the union of both sets.
+    pub fn union(&self, other: Self) -> Self {
+        SparseEnumSet { bits: self.bits | other.bits, phantom: PhantomData }
+    }
+
+    /// Returns the intersection of both sets.
+    pub fn intersection(&self, other: Self) -> Self {
+        SparseEnumSet { bits: self.bits & other.bits, phantom: PhantomData }
+    }
+
+    /// Returns an iterator over the set's elements, in the order of
+    /// `SparseCLike::CODES`.
+    pub fn iter(&self) -> SparseIter<E> {
+        SparseIter { bits: self.bits, phantom: PhantomData }
+    }
+}
+
+impl<E: SparseCLike> Default for SparseEnumSet<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: SparseCLike + fmt::Debug> fmt::Debug for SparseEnumSet<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<E: SparseCLike> FromIterator<E> for SparseEnumSet<E> {
+    fn from_iter<I: IntoIterator<Item = E>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<E: SparseCLike> Extend<E> for SparseEnumSet<E> {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        for element in iter {
+            self.insert(element);
+        }
+    }
+}
+
+impl<E: SparseCLike> IntoIterator for SparseEnumSet<E> {
+    type Item = E;
+    type IntoIter = SparseIter<E>;
+
+    fn into_iter(self) -> SparseIter<E> {
+        self.iter()
+    }
+}
+
+/// An iterator over a `SparseEnumSet`.
+pub struct SparseIter<E> {
+    bits: u32,
+    phantom: PhantomData<E>,
+}
+
+impl<E> Clone for SparseIter<E> {
+    fn clone(&self) -> Self {
+        SparseIter { bits: self.bits, phantom: PhantomData }
+    }
+}
+
+impl<E: SparseCLike> Iterator for SparseIter<E> {
+    type Item = E;
+
+    fn next(&mut self) -> Option<E> {
+        if self.bits == 0 {
+            return None;
+        }
+        let index = self.bits.trailing_zeros() as usize;
+        self.bits &= self.bits - 1;
+        // Safe because only positions of entries of `CODES` are set.
+        Some(unsafe { E::from_u32(E::CODES[index]) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl<E: SparseCLike> ExactSizeIterator for SparseIter<E> {}
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+
+    use CLike;
+    use super::{SparseCLike, SparseEnumSet};
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+    #[repr(u32)]
+    enum Status {
+        Ok = 200,
+        NotFound = 404,
+        Custom = 10_000,
+    }
+
+    impl CLike for Status {
+        fn to_u32(&self) -> u32 {
+            *self as u32
+        }
+
+        unsafe fn from_u32(v: u32) -> Status {
+            mem::transmute(v)
+        }
+    }
+
+    unsafe impl SparseCLike for Status {
+        const CODES: &'static [u32] = &[200, 404, 10_000];
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set = SparseEnumSet::new();
+        assert!(set.insert(Status::Custom));
+        assert!(!set.insert(Status::Custom));
+        assert!(set.insert(Status::Ok));
+        assert!(set.contains(Status::Custom));
+        assert!(!set.contains(Status::NotFound));
+        assert_eq!(2, set.len());
+        assert!(set.remove(Status::Custom));
+        assert!(!set.remove(Status::Custom));
+        assert_eq!(vec![Status::Ok], set.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_operations() {
+        let a: SparseEnumSet<Status> = vec![Status::Ok, Status::Custom].into_iter().collect();
+        let b: SparseEnumSet<Status> = vec![Status::NotFound, Status::Custom].into_iter().collect();
+        assert_eq!(3, a.union(b).len());
+        assert_eq!(vec![Status::Custom], a.intersection(b).into_iter().collect::<Vec<_>>());
+        assert_eq!("{Ok, Custom}", format!("{:?}", a));
+        assert!(SparseEnumSet::<Status>::default().is_empty());
+    }
+}
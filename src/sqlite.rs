@@ -0,0 +1,75 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Use of sets as SQLite values with `rusqlite`.
+//!
+//! A set is stored as its bits in an `INTEGER` column. Reading fails with
+//! `FromSqlError::OutOfRange` if bits are set which do not correspond to a
+//! variant.
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::Result;
+
+use {universe, CLike, EnumSet, Finite};
+
+impl<E: CLike> ToSql for EnumSet<E> {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(i64::from(self.bits)))
+    }
+}
+
+impl<E: Finite> FromSql for EnumSet<E> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let bits = value.as_i64()?;
+        if bits >= 0 && bits & !i64::from(universe::<E>()) == 0 {
+            Ok(EnumSet::new_with_bits(bits as u32))
+        } else {
+            Err(FromSqlError::OutOfRange(bits))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::{Connection, Error};
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE t (flags INTEGER NOT NULL)", []).unwrap();
+        conn.execute("INSERT INTO t (flags) VALUES (?1)", [set(&[A, C])]).unwrap();
+        let flags: EnumSet<Foo> = conn.query_row("SELECT flags FROM t", [], |row| row.get(0))
+                                      .unwrap();
+        assert_eq!(set(&[A, C]), flags);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let conn = Connection::open_in_memory().unwrap();
+        let get = |sql| conn.query_row(sql, [], |row| row.get::<_, EnumSet<Foo>>(0));
+        match get("SELECT 8") {
+            Err(Error::IntegralValueOutOfRange(0, 8)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        match get("SELECT -1") {
+            Err(Error::IntegralValueOutOfRange(0, -1)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        match get("SELECT 'A'") {
+            Err(Error::InvalidColumnType(..)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A hash of sets which is stable across builds.
+//!
+//! The hash is 64-bit FNV-1a, with offset basis `0xcbf29ce484222325` and
+//! prime `0x100000001b3`, over the little-endian fingerprint of the enum
+//! followed by the little-endian bits of the set. The algorithm and its
+//! input are part of the public interface: a set of a given enum hashes to
+//! the same value on every platform and in every future version of this
+//! crate, so the hash may be persisted or sent to other processes.
+
+use std::fmt;
+
+use {versioned, EnumSet, Finite};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl<E: Finite + fmt::Debug> EnumSet<E> {
+    /// Returns a 64-bit hash of the set which does not depend on the
+    /// platform, the compiler or the version of this crate.
+    ///
+    /// The hash is the 64-bit FNV-1a hash of eight bytes: the fingerprint of
+    /// the variants of `E` used by `encode_versioned`, followed by the bits
+    /// of the set, both as little-endian `u32`s. Sets of enums whose
+    /// variants differ in name or order therefore hash differently.
+    pub fn stable_hash64(&self) -> u64 {
+        let mut bytes = [0; 8];
+        bytes[..4].copy_from_slice(&versioned::fingerprint::<E>().to_le_bytes());
+        bytes[4..].copy_from_slice(&self.bits.to_le_bytes());
+        bytes.iter().fold(FNV_OFFSET_BASIS, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    #[test]
+    fn test_stable_hash64() {
+        // These values must never change.
+        assert_eq!(14660723415818811283, set(&[]).stable_hash64());
+        assert_eq!(12350381312442559814, set(&[A, C]).stable_hash64());
+    }
+
+    #[test]
+    fn test_distinct() {
+        let hashes: Vec<u64> = EnumSet::<Foo>::exhaustive().map(|s| s.stable_hash64()).collect();
+        for (i, a) in hashes.iter().enumerate() {
+            assert!(hashes[i + 1..].iter().all(|b| a != b));
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// Asserts at compile time that `EnumSet` constants are pairwise disjoint.
+///
+/// ```
+/// #[macro_use]
+/// extern crate enum_set;
+///
+/// enum_set_flags! {
+///     pub enum Route {
+///         Local,
+///         Lan,
+///         Wan,
+///     }
+///
+///     impl Route {
+///         pub const PRIVATE = Local | Lan;
+///         pub const PUBLIC = Wan;
+///     }
+/// }
+///
+/// static_assert_disjoint!(Route::PRIVATE, Route::PUBLIC);
+///
+/// fn main() {}
+/// ```
+///
+/// Overlapping sets fail to compile:
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate enum_set;
+///
+/// enum_set_flags! {
+///     pub enum Route {
+///         Local,
+///         Lan,
+///         Wan,
+///     }
+///
+///     impl Route {
+///         pub const PRIVATE = Local | Lan;
+///         pub const NEARBY = Lan;
+///     }
+/// }
+///
+/// static_assert_disjoint!(Route::PRIVATE, Route::NEARBY);
+///
+/// fn main() {}
+/// ```
+#[macro_export]
+macro_rules! static_assert_disjoint {
+    ($($set:expr),+ $(,)*) => {
+        const _: () = {
+            let sets = [$($crate::EnumSet::__bits(&$set)),+];
+            let mut i = 0;
+            while i < sets.len() {
+                let mut j = i + 1;
+                while j < sets.len() {
+                    assert!(sets[i] & sets[j] == 0, "EnumSet constants are not disjoint");
+                    j += 1;
+                }
+                i += 1;
+            }
+        };
+    };
+}
+
+/// Asserts at compile time that an `EnumSet` constant contains every
+/// element of one or more others.
+///
+/// ```
+/// #[macro_use]
+/// extern crate enum_set;
+///
+/// enum_set_flags! {
+///     pub enum Route {
+///         Local,
+///         Lan,
+///         Wan,
+///     }
+///
+///     impl Route {
+///         pub const ANY = Local | Lan | Wan;
+///         pub const PRIVATE = Local | Lan;
+///     }
+/// }
+///
+/// static_assert_covers!(Route::ANY, Route::PRIVATE);
+///
+/// fn main() {}
+/// ```
+#[macro_export]
+macro_rules! static_assert_covers {
+    ($outer:expr, $($inner:expr),+ $(,)*) => {
+        const _: () = {
+            let outer = $crate::EnumSet::__bits(&$outer);
+            $(
+                assert!($crate::EnumSet::__bits(&$inner) & !outer == 0,
+                        "EnumSet constant does not cover another");
+            )+
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::Foo;
+
+    use EnumSet;
+
+    const FIRST: EnumSet<Foo> = EnumSet::__from_bits(0b001);
+    const REST: EnumSet<Foo> = EnumSet::__from_bits(0b110);
+    const ALL: EnumSet<Foo> = EnumSet::__from_bits(0b111);
+
+    static_assert_disjoint!(FIRST, REST);
+    static_assert_disjoint!(FIRST);
+    static_assert_covers!(ALL, FIRST, REST);
+    static_assert_covers!(REST, REST);
+}
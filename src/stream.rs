@@ -0,0 +1,102 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reading and writing sets from byte streams.
+
+use std::fmt;
+use std::io;
+
+use {universe, versioned, CLike, EnumSet, Finite};
+
+impl<E: CLike> EnumSet<E> {
+    /// Writes the bits of the set to `writer` as a little-endian `u32`.
+    ///
+    /// The result can be read back with `read_from`.
+    pub fn write_to<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.bits.to_le_bytes())
+    }
+}
+
+impl<E: Finite> EnumSet<E> {
+    /// Reads a set written by `write_to` from `reader`.
+    ///
+    /// Fails with `io::ErrorKind::InvalidData` if bits are set which do not
+    /// correspond to a variant.
+    pub fn read_from<R: io::Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        let mut bytes = [0; 4];
+        reader.read_exact(&mut bytes)?;
+        let bits = u32::from_le_bytes(bytes);
+        if bits & !universe::<E>() != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                      "mask contains an unknown variant"));
+        }
+        Ok(Self::new_with_bits(bits))
+    }
+}
+
+impl<E: Finite + fmt::Debug> EnumSet<E> {
+    /// Writes the set to `writer` using the encoding of `encode_versioned`,
+    /// which is prefixed with a format version and a fingerprint of the
+    /// variants of `E`.
+    pub fn write_versioned_to<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.encode_versioned())
+    }
+
+    /// Reads a set written by `write_versioned_to` from `reader`.
+    ///
+    /// Decoding errors are reported as `io::ErrorKind::InvalidData` holding
+    /// a `VersionedDecodeError`.
+    pub fn read_versioned_from<R: io::Read + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        let mut bytes = [0; versioned::ENCODED_LEN];
+        reader.read_exact(&mut bytes)?;
+        Self::decode_versioned(&bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor};
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use {EnumSet, VersionedDecodeError};
+
+    #[test]
+    fn test_round_trip() {
+        let mut out = Vec::new();
+        set(&[A, C]).write_to(&mut out).unwrap();
+        set(&[B]).write_to(&mut out).unwrap();
+        assert_eq!(vec![5, 0, 0, 0, 2, 0, 0, 0], out);
+
+        let mut reader = Cursor::new(out);
+        assert_eq!(set(&[A, C]), EnumSet::read_from(&mut reader).unwrap());
+        assert_eq!(set(&[B]), EnumSet::read_from(&mut reader).unwrap());
+        let err = EnumSet::<Foo>::read_from(&mut reader).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+
+        let err = EnumSet::<Foo>::read_from(&mut &[8, 0, 0, 0][..]).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn test_versioned_round_trip() {
+        let mut out = Vec::new();
+        set(&[A, C]).write_versioned_to(&mut out).unwrap();
+        assert_eq!(EnumSet::<Foo>::MAX_VERSIONED_LEN, out.len());
+        assert_eq!(set(&[A, C]), EnumSet::read_versioned_from(&mut &out[..]).unwrap());
+
+        out[0] = 2;
+        let err = EnumSet::<Foo>::read_versioned_from(&mut &out[..]).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        assert_eq!(Some(&VersionedDecodeError::UnsupportedVersion(2)),
+                   err.get_ref().and_then(|err| err.downcast_ref()));
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Checks of the rules this crate relies on, for use in downstream tests.
+//!
+//! `check_clike` verifies a hand-written `CLike` and `Finite`
+//! implementation, `check_set_laws` and `check_iterators` verify the set
+//! algebra and iterator contracts for an element type, and `check_backend`
+//! verifies a custom `EnumSetLike` implementation against `EnumSet`. The
+//! `enum_set_testsuite!` macro generates a test for each of the first three.
+//!
+//! The checks panic with a description of the first violated rule. Apart
+//! from `check_clike`, they need the elements to be `Copy` and `PartialEq`.
+
+use std::fmt;
+
+use {EnumSet, EnumSetLike, Finite};
+
+/// Returns the sets the laws are checked on: every set if there are at most
+/// eight variants, and a selection of sets otherwise.
+fn samples<E: Finite>() -> Vec<EnumSet<E>> {
+    if E::VARIANT_COUNT <= 8 {
+        return EnumSet::<E>::exhaustive().collect();
+    }
+    let all = EnumSet::<E>::all().bits;
+    let mut masks = vec![0, all, all & 0x5555_5555, all & 0xaaaa_aaaa, all & 0x0f0f_0f0f];
+    masks.extend((0..E::VARIANT_COUNT).map(|index| 1 << index));
+    masks.extend((0..E::VARIANT_COUNT).map(|index| all & !(1 << index)));
+    masks.into_iter().map(EnumSet::new_with_bits).collect()
+}
+
+/// Checks that `to_u32` and `from_u32` are inverse to each other for every
+/// value below `VARIANT_COUNT`, and that `VARIANT_COUNT` is at most 32.
+pub fn check_clike<E: Finite + fmt::Debug>() {
+    assert!(E::VARIANT_COUNT <= 32, "VARIANT_COUNT is {}, above 32", E::VARIANT_COUNT);
+    for index in 0..E::VARIANT_COUNT {
+        // Safe because of the contract of `Finite`.
+        let variant = unsafe { E::from_u32(index) };
+        assert_eq!(index, variant.to_u32(), "to_u32(from_u32({})) of {:?}", index, variant);
+    }
+}
+
+/// Checks the laws of the set operations on sets of `E`.
+pub fn check_set_laws<E: Finite + Copy + PartialEq + fmt::Debug>() {
+    let empty = EnumSet::<E>::new();
+    let all = EnumSet::<E>::all();
+    let samples = samples::<E>();
+    for &a in &samples {
+        assert_eq!(a, a | empty, "{:?} | {{}}", a);
+        assert_eq!(a, a & all, "{:?} & all", a);
+        assert_eq!(a, !!a, "!!{:?}", a);
+        assert_eq!(all, a | !a, "{:?} | !{:?}", a, a);
+        assert_eq!(a.len(), a.iter().count(), "len of {:?}", a);
+        assert_eq!(a.is_empty(), a == empty, "is_empty of {:?}", a);
+        for &b in &samples {
+            assert_eq!(a | b, b | a, "{:?} | {:?}", a, b);
+            assert_eq!(a & b, b & a, "{:?} & {:?}", a, b);
+            assert_eq!(a - b, a & !b, "{:?} - {:?}", a, b);
+            assert_eq!(a ^ b, (a | b) - (a & b), "{:?} ^ {:?}", a, b);
+            assert_eq!(!(a | b), !a & !b, "!({:?} | {:?})", a, b);
+            assert_eq!(!(a & b), !a | !b, "!({:?} & {:?})", a, b);
+            assert_eq!(a.is_subset(&b), a | b == b, "{:?} <= {:?}", a, b);
+            assert_eq!(a.is_superset(&b), b.is_subset(&a), "{:?} >= {:?}", a, b);
+            assert_eq!(a.is_disjoint(&b), (a & b).is_empty(), "{:?} disjoint {:?}", a, b);
+            for &c in samples.iter().step_by(3) {
+                assert_eq!((a | b) | c, a | (b | c), "({:?} | {:?}) | {:?}", a, b, c);
+                assert_eq!((a & b) & c, a & (b & c), "({:?} & {:?}) & {:?}", a, b, c);
+                assert_eq!(a & (b | c), (a & b) | (a & c), "{:?} & ({:?} | {:?})", a, b, c);
+            }
+        }
+    }
+}
+
+/// Checks that iterating over sets of `E` yields exactly their elements in
+/// increasing order, with exact size hints.
+pub fn check_iterators<E: Finite + Copy + PartialEq + fmt::Debug>() {
+    for set in samples::<E>() {
+        let mut iter = set.iter();
+        let mut previous = None;
+        for remaining in (0..set.len()).rev() {
+            let element = iter.next().expect("iterator yielded too few elements");
+            let index = element.to_u32();
+            assert!(set.contains(element), "{:?} yielded {:?}", set, element);
+            assert!(previous.is_none_or(|previous| previous < index),
+                    "{:?} yielded {:?} out of order", set, element);
+            previous = Some(index);
+            assert_eq!((remaining, Some(remaining)), iter.size_hint(), "size_hint of {:?}", set);
+        }
+        assert!(iter.next().is_none(), "{:?} yielded too many elements", set);
+        assert_eq!(set, set.iter().collect(), "collecting {:?}", set);
+    }
+}
+
+/// Checks that a set created by `new` behaves like an `EnumSet` under a
+/// sequence of insertions and removals of every variant.
+///
+/// `new` must return an empty set.
+pub fn check_backend<E, S, F>(new: F)
+    where E: Finite + Copy + PartialEq + fmt::Debug,
+          S: EnumSetLike<E>,
+          F: Fn() -> S
+{
+    let mut set = new();
+    let mut model = EnumSet::<E>::new();
+    assert_eq!(model, set.to_enum_set(), "new set is not empty");
+    // Insert every variant, then remove every other one, then insert them
+    // again, comparing every step with `EnumSet`.
+    let steps = (0..E::VARIANT_COUNT).map(|index| (index, true))
+        .chain((0..E::VARIANT_COUNT).step_by(2).map(|index| (index, false)))
+        .chain((0..E::VARIANT_COUNT).map(|index| (index, true)));
+    for (index, insert) in steps {
+        // Safe because of the contract of `Finite`.
+        let value = unsafe { E::from_u32(index) };
+        let (result, expected_result) = if insert {
+            (set.insert(value), model.insert(value))
+        } else {
+            (set.remove(value), model.remove(value))
+        };
+        assert_eq!(expected_result, result, "result of inserting or removing {:?}", value);
+        assert_eq!(model, set.to_enum_set(), "set after inserting or removing {:?}", value);
+        assert_eq!(model.len(), set.len(), "len after inserting or removing {:?}", value);
+        assert_eq!(model.contains(value), set.contains(value), "contains {:?}", value);
+    }
+}
+
+/// Generates tests running the checks of `testsuite` for an element type.
+///
+/// ```
+/// #[macro_use]
+/// extern crate enum_set;
+///
+/// enum_set_flags! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Flag {
+///         A, B, C,
+///     }
+/// }
+///
+/// enum_set_testsuite!(flag_conformance, Flag);
+///
+/// fn main() {}
+/// ```
+#[macro_export]
+macro_rules! enum_set_testsuite {
+    ($name:ident, $ty:ty) => {
+        #[cfg(test)]
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #[test]
+            fn clike() {
+                $crate::testsuite::check_clike::<$ty>();
+            }
+
+            #[test]
+            fn set_laws() {
+                $crate::testsuite::check_set_laws::<$ty>();
+            }
+
+            #[test]
+            fn iterators() {
+                $crate::testsuite::check_iterators::<$ty>();
+            }
+
+            #[test]
+            fn backend() {
+                $crate::testsuite::check_backend($crate::EnumSet::<$ty>::new);
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crdt::ORSet;
+    use tests::Foo;
+
+    use {EnumSet, OpenEnumSet, WatchedEnumSet};
+
+    enum_set_testsuite!(foo, Foo);
+
+    #[test]
+    fn test_backends() {
+        super::check_backend(|| WatchedEnumSet::new(EnumSet::<Foo>::new(), |_, _| {}));
+        super::check_backend(OpenEnumSet::<Foo>::new);
+        super::check_backend(|| ORSet::<Foo>::new(1));
+    }
+}
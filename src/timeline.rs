@@ -0,0 +1,200 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A compact encoding of sequences of sets.
+//!
+//! Every set is stored as the XOR of its bits with the bits of the previous
+//! set (the first set is compared to an empty set). Consecutive equal deltas
+//! are stored once, with the length of the run. Both the run lengths and the
+//! deltas are written as LEB128 variable-length integers, so a sequence of
+//! sets which rarely changes takes only a few bytes per change.
+
+use std::error;
+use std::fmt;
+use std::marker::PhantomData;
+
+use {universe, CLike, EnumSet, Finite};
+
+/// An error returned when decoding a sequence of sets fails.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input ends in the middle of a run.
+    Truncated,
+    /// A run length or delta is out of range.
+    Overflow,
+    /// A run has a length of zero.
+    EmptyRun,
+    /// A decoded set contains bits which do not correspond to a variant.
+    UnknownVariant,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let description = match *self {
+            DecodeError::Truncated => "unexpected end of input",
+            DecodeError::Overflow => "integer out of range",
+            DecodeError::EmptyRun => "run of length zero",
+            DecodeError::UnknownVariant => "set contains an unknown variant",
+        };
+        write!(fmt, "invalid timeline: {}", description)
+    }
+}
+
+impl error::Error for DecodeError {}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(input: &mut &[u8]) -> Result<u64, DecodeError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = input.split_first().ok_or(DecodeError::Truncated)?;
+        *input = rest;
+        let bits = u64::from(byte & 0x7f);
+        if shift == 63 && bits > 1 {
+            return Err(DecodeError::Overflow);
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(DecodeError::Overflow)
+}
+
+/// Encodes a sequence of sets.
+pub fn encode<E: CLike, I: IntoIterator<Item = EnumSet<E>>>(sets: I) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut previous = 0;
+    let mut run: Option<(u32, u64)> = None;
+    for set in sets {
+        let delta = set.bits ^ previous;
+        previous = set.bits;
+        run = match run {
+            Some((run_delta, length)) if run_delta == delta => Some((delta, length + 1)),
+            Some((run_delta, length)) => {
+                write_varint(&mut out, length);
+                write_varint(&mut out, u64::from(run_delta));
+                Some((delta, 1))
+            }
+            None => Some((delta, 1)),
+        };
+    }
+    if let Some((run_delta, length)) = run {
+        write_varint(&mut out, length);
+        write_varint(&mut out, u64::from(run_delta));
+    }
+    out
+}
+
+/// Returns an iterator decoding a sequence of sets encoded by `encode`.
+///
+/// The iterator stops after returning an error.
+pub fn decode<E: Finite>(input: &[u8]) -> Decode<'_, E> {
+    Decode { input, current: 0, delta: 0, remaining: 0, failed: false, phantom: PhantomData }
+}
+
+/// An iterator decoding a sequence of sets.
+pub struct Decode<'a, E> {
+    input: &'a [u8],
+    current: u32,
+    delta: u32,
+    remaining: u64,
+    failed: bool,
+    phantom: PhantomData<*mut E>,
+}
+
+impl<'a, E: Finite> Decode<'a, E> {
+    fn next_run(&mut self) -> Result<(), DecodeError> {
+        let length = read_varint(&mut self.input)?;
+        let delta = read_varint(&mut self.input)?;
+        if length == 0 {
+            return Err(DecodeError::EmptyRun);
+        }
+        if delta > u64::from(u32::MAX) {
+            return Err(DecodeError::Overflow);
+        }
+        self.remaining = length;
+        self.delta = delta as u32;
+        Ok(())
+    }
+}
+
+impl<'a, E: Finite> Iterator for Decode<'a, E> {
+    type Item = Result<EnumSet<E>, DecodeError>;
+
+    fn next(&mut self) -> Option<Result<EnumSet<E>, DecodeError>> {
+        if self.failed {
+            return None;
+        }
+        if self.remaining == 0 {
+            if self.input.is_empty() {
+                return None;
+            }
+            if let Err(err) = self.next_run() {
+                self.failed = true;
+                return Some(Err(err));
+            }
+        }
+        self.remaining -= 1;
+        self.current ^= self.delta;
+        if self.current & !universe::<E>() != 0 {
+            self.failed = true;
+            return Some(Err(DecodeError::UnknownVariant));
+        }
+        Some(Ok(EnumSet::new_with_bits(self.current)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::{decode, encode, DecodeError};
+
+    #[test]
+    fn test_round_trip() {
+        let mut sets = vec![set(&[A]); 1000];
+        sets.extend(vec![set(&[A, C]); 200]);
+        sets.push(EnumSet::new());
+        sets.push(set(&[B]));
+        sets.push(EnumSet::new());
+        sets.push(set(&[B]));
+
+        let encoded = encode(sets.iter().cloned());
+        assert_eq!(vec![1, 1, 0xe7, 0x07, 0, 1, 4, 0xc7, 0x01, 0, 1, 5, 3, 2], encoded);
+        let decoded: Result<Vec<_>, _> = decode(&encoded).collect();
+        assert_eq!(Ok(sets), decoded);
+    }
+
+    #[test]
+    fn test_empty() {
+        let encoded = encode(Vec::<EnumSet<Foo>>::new());
+        assert!(encoded.is_empty());
+        assert_eq!(0, decode::<Foo>(&encoded).count());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(vec![Err(DecodeError::Truncated)], decode::<Foo>(&[1]).collect::<Vec<_>>());
+        assert_eq!(vec![Err(DecodeError::EmptyRun)], decode::<Foo>(&[0, 1]).collect::<Vec<_>>());
+        assert_eq!(vec![Ok(set(&[A])), Err(DecodeError::UnknownVariant)],
+                   decode::<Foo>(&[1, 1, 1, 8]).collect::<Vec<_>>());
+        assert_eq!(vec![Err(DecodeError::Overflow)],
+                   decode::<Foo>(&[1, 0x80, 0x80, 0x80, 0x80, 0x10]).collect::<Vec<_>>());
+    }
+}
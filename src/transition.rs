@@ -0,0 +1,179 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! State machine transition tables.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use {bit, CLike, EnumSet};
+
+/// A table of the allowed transitions between the states of a state machine.
+///
+/// The successors of every state are stored as an `EnumSet`. A table can be
+/// built with `allow` and `allow_all`, or declared with the
+/// `transition_table!` macro:
+///
+/// ```
+/// #[macro_use]
+/// extern crate enum_set;
+///
+/// use enum_set::CLike;
+/// use std::mem;
+///
+/// #[derive(Clone, Copy, Debug)]
+/// #[repr(u32)]
+/// enum State {
+///     Idle, Running, Done
+/// }
+///
+/// impl CLike for State {
+///     fn to_u32(&self) -> u32 {
+///         *self as u32
+///     }
+///
+///     unsafe fn from_u32(v: u32) -> State {
+///         mem::transmute(v)
+///     }
+/// }
+///
+/// fn main() {
+///     let table = transition_table! {
+///         State::Idle => [State::Running],
+///         State::Running => [State::Idle, State::Done],
+///     };
+///     assert!(table.can_transition(State::Running, State::Done));
+///     assert!(!table.can_transition(State::Done, State::Idle));
+/// }
+/// ```
+pub struct TransitionTable<S> {
+    successors: [u32; 32],
+    phantom: PhantomData<S>,
+}
+
+fn index<S: CLike>(state: &S) -> usize {
+    bit(state).trailing_zeros() as usize
+}
+
+impl<S: CLike> TransitionTable<S> {
+    /// Returns a `TransitionTable` without any allowed transitions.
+    pub fn new() -> Self {
+        TransitionTable { successors: [0; 32], phantom: PhantomData }
+    }
+
+    /// Allows the transition from `from` to `to`.
+    pub fn allow(mut self, from: S, to: S) -> Self {
+        self.successors[index(&from)] |= bit(&to);
+        self
+    }
+
+    /// Allows the transitions from `from` to every state in `to`.
+    pub fn allow_all(mut self, from: S, to: EnumSet<S>) -> Self {
+        self.successors[index(&from)] |= to.bits;
+        self
+    }
+
+    /// Replaces the allowed successors of `from` with `to`.
+    pub fn set_successors(&mut self, from: S, to: EnumSet<S>) {
+        self.successors[index(&from)] = to.bits;
+    }
+
+    /// Returns the states which can be reached from `from` in one transition.
+    pub fn successors(&self, from: S) -> EnumSet<S> {
+        EnumSet::new_with_bits(self.successors[index(&from)])
+    }
+
+    /// Returns `true` if the transition from `from` to `to` is allowed.
+    pub fn can_transition(&self, from: S, to: S) -> bool {
+        self.successors(from).contains(&to)
+    }
+}
+
+impl<S: CLike> Default for TransitionTable<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Clone for TransitionTable<S> {
+    fn clone(&self) -> Self {
+        TransitionTable { successors: self.successors, phantom: PhantomData }
+    }
+}
+
+impl<S: CLike + fmt::Debug> fmt::Debug for TransitionTable<S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let mut map = fmt.debug_map();
+        for (index, &successors) in self.successors.iter().enumerate() {
+            if successors != 0 {
+                // Safe because only states passed to `allow`, `allow_all` or
+                // `set_successors` can have successors.
+                let state: S = unsafe { CLike::from_u32(index as u32) };
+                map.entry(&state, &EnumSet::<S>::new_with_bits(successors));
+            }
+        }
+        map.finish()
+    }
+}
+
+/// Declares a `TransitionTable` from a list of states and their successors.
+///
+/// See `TransitionTable` for an example.
+#[macro_export]
+macro_rules! transition_table {
+    ($($from:expr => [$($to:expr),* $(,)*]),* $(,)*) => {
+        $crate::TransitionTable::new()
+            $($(.allow($from, $to))*)*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::Foo;
+    use tests::Foo::*;
+
+    use EnumSet;
+    use super::TransitionTable;
+
+    #[test]
+    fn test_builder() {
+        let mut to: EnumSet<Foo> = EnumSet::new();
+        to.insert(A);
+        to.insert(B);
+        let table = TransitionTable::new().allow(A, B).allow_all(C, to);
+
+        assert!(table.can_transition(A, B));
+        assert!(!table.can_transition(B, A));
+        assert!(!table.can_transition(C, C));
+        assert_eq!(to, table.successors(C));
+        assert!(table.successors(B).is_empty());
+    }
+
+    #[test]
+    fn test_set_successors() {
+        let mut table = TransitionTable::new().allow(A, B);
+        table.set_successors(A, EnumSet::new());
+        assert!(!table.can_transition(A, B));
+    }
+
+    #[test]
+    fn test_macro() {
+        let table: TransitionTable<Foo> = transition_table! {
+            A => [B, C],
+            B => [],
+            C => [A,],
+        };
+        assert!(table.can_transition(A, B));
+        assert!(table.can_transition(A, C));
+        assert!(table.can_transition(C, A));
+        assert!(table.successors(B).is_empty());
+        assert_eq!("{A: {B, C}, C: {A}}", format!("{:?}", table));
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::borrow;
+use std::fmt;
+use std::marker::PhantomData;
+
+use {bit, CLike, EnumSet};
+
+/// The setting of a variant in a `TriStateMap`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TriState {
+    /// The variant is explicitly enabled.
+    On,
+    /// The variant is explicitly disabled.
+    Off,
+    /// The variant takes its setting from the defaults.
+    Inherit,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+/// A map from variants to a `TriState` setting.
+///
+/// Every variant starts out as `Inherit`. `resolve` turns the map into a set
+/// given the set of variants enabled by default, so maps can be stacked to
+/// express hierarchical configuration.
+pub struct TriStateMap<E> {
+    on: u32,
+    off: u32,
+    phantom: PhantomData<E>,
+}
+
+impl<E: CLike> TriStateMap<E> {
+    /// Returns a map where every variant inherits its setting.
+    pub fn new() -> Self {
+        TriStateMap { on: 0, off: 0, phantom: PhantomData }
+    }
+
+    /// Returns the setting of `value`.
+    pub fn get<V: borrow::Borrow<E>>(&self, value: V) -> TriState {
+        let value = bit(value.borrow());
+        if self.on & value != 0 {
+            TriState::On
+        } else if self.off & value != 0 {
+            TriState::Off
+        } else {
+            TriState::Inherit
+        }
+    }
+
+    /// Changes the setting of `value`, returning its previous setting.
+    pub fn set(&mut self, value: E, state: TriState) -> TriState {
+        let previous = self.get(&value);
+        let value = bit(&value);
+        self.on &= !value;
+        self.off &= !value;
+        match state {
+            TriState::On => self.on |= value,
+            TriState::Off => self.off |= value,
+            TriState::Inherit => {}
+        }
+        previous
+    }
+
+    /// Returns the variants which are explicitly enabled.
+    pub fn on(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.on)
+    }
+
+    /// Returns the variants which are explicitly disabled.
+    pub fn off(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.off)
+    }
+
+    /// Returns the enabled variants, taking the variants which inherit
+    /// their setting from `defaults`.
+    pub fn resolve(&self, defaults: EnumSet<E>) -> EnumSet<E> {
+        EnumSet::new_with_bits((defaults.bits | self.on) & !self.off)
+    }
+
+    /// Returns the map with the explicit settings of `child` replacing its
+    /// own, as if `child` were resolved against the result of the map.
+    pub fn overlay(&self, child: &Self) -> Self {
+        let explicit = child.on | child.off;
+        TriStateMap {
+            on: (self.on & !explicit) | child.on,
+            off: (self.off & !explicit) | child.off,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<E: CLike> Default for TriStateMap<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: CLike + fmt::Debug> fmt::Debug for TriStateMap<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("TriStateMap")
+           .field("on", &self.on())
+           .field("off", &self.off())
+           .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tests::set;
+    use tests::Foo::*;
+
+    use super::{TriState, TriStateMap};
+
+    #[test]
+    fn test_set() {
+        let mut map = TriStateMap::new();
+        assert_eq!(TriState::Inherit, map.set(A, TriState::On));
+        assert_eq!(TriState::On, map.set(A, TriState::Off));
+        assert_eq!(TriState::Off, map.get(A));
+        assert_eq!(TriState::Inherit, map.get(B));
+        map.set(B, TriState::On);
+        assert_eq!(set(&[B]), map.on());
+        assert_eq!(set(&[A]), map.off());
+        assert_eq!(TriState::On, map.set(B, TriState::Inherit));
+        assert!(map.on().is_empty());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut map = TriStateMap::new();
+        map.set(A, TriState::Off);
+        map.set(B, TriState::On);
+        assert_eq!(set(&[B, C]), map.resolve(set(&[A, C])));
+        assert_eq!(set(&[A]), TriStateMap::default().resolve(set(&[A])));
+    }
+
+    #[test]
+    fn test_overlay() {
+        let mut parent = TriStateMap::new();
+        parent.set(A, TriState::On);
+        parent.set(B, TriState::On);
+        let mut child = TriStateMap::new();
+        child.set(B, TriState::Off);
+        child.set(C, TriState::On);
+
+        let merged = parent.overlay(&child);
+        assert_eq!(set(&[A, C]), merged.on());
+        assert_eq!(set(&[B]), merged.off());
+        let defaults = set(&[B]);
+        assert_eq!(child.resolve(parent.resolve(defaults)), merged.resolve(defaults));
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Formatting of sets with `ufmt`.
+
+use ufmt::{uDebug, uDisplay, uWrite, Formatter};
+
+use {CLike, EnumSet};
+
+/// Formats the set like its `Debug` implementation, as `{A, B}`.
+impl<E: CLike + uDebug> uDebug for EnumSet<E> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        let mut set = f.debug_set()?;
+        for element in self.iter() {
+            set.entry(&element)?;
+        }
+        set.finish()
+    }
+}
+
+/// Formats the elements of the set separated by `, `.
+impl<E: CLike + uDisplay> uDisplay for EnumSet<E> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        for (index, element) in self.iter().enumerate() {
+            if index != 0 {
+                f.write_str(", ")?;
+            }
+            uDisplay::fmt(&element, f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use ufmt::{uDebug, uDisplay, uWrite, uwrite, Formatter};
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+
+    struct Buffer(String);
+
+    impl uWrite for Buffer {
+        type Error = Infallible;
+
+        fn write_str(&mut self, s: &str) -> Result<(), Infallible> {
+            self.0.push_str(s);
+            Ok(())
+        }
+    }
+
+    impl uDebug for Foo {
+        fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+            f.write_str(match *self { A => "A", B => "B", C => "C" })
+        }
+    }
+
+    impl uDisplay for Foo {
+        fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+            f.write_str(match *self { A => "a", B => "b", C => "c" })
+        }
+    }
+
+    #[test]
+    fn test_udebug() {
+        let mut buffer = Buffer(String::new());
+        uwrite!(buffer, "{:?} {:?}", set(&[A, C]), set(&[])).unwrap();
+        assert_eq!("{A, C} {}", buffer.0);
+    }
+
+    #[test]
+    fn test_udisplay() {
+        let mut buffer = Buffer(String::new());
+        uwrite!(buffer, "[{}] [{}]", set(&[A, B, C]), set(&[])).unwrap();
+        assert_eq!("[a, b, c] []", buffer.0);
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Passing sets over UniFFI.
+//!
+//! A set is passed as its bits, so it appears as a `u32` in the generated
+//! bindings. Lifting fails if bits are set which do not correspond to a
+//! variant.
+
+use uniffi_core::deps::anyhow::anyhow;
+use uniffi_core::{derive_ffi_traits, FfiConverter, MetadataBuffer, Result};
+
+use {universe, EnumSet, Finite};
+
+unsafe impl<UT, E: Finite> FfiConverter<UT> for EnumSet<E> {
+    type FfiType = u32;
+
+    fn lower(obj: Self) -> u32 {
+        obj.bits
+    }
+
+    fn try_lift(v: u32) -> Result<Self> {
+        if v & !universe::<E>() == 0 {
+            Ok(EnumSet::new_with_bits(v))
+        } else {
+            Err(anyhow!("invalid EnumSet bits {:#x}", v))
+        }
+    }
+
+    fn write(obj: Self, buf: &mut Vec<u8>) {
+        <u32 as FfiConverter<UT>>::write(obj.bits, buf)
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self> {
+        let bits = <u32 as FfiConverter<UT>>::try_read(buf)?;
+        <Self as FfiConverter<UT>>::try_lift(bits)
+    }
+
+    const TYPE_ID_META: MetadataBuffer = <u32 as FfiConverter<UT>>::TYPE_ID_META;
+}
+
+derive_ffi_traits!(impl<UT, E> Lower<UT> for EnumSet<E> where E: Finite);
+derive_ffi_traits!(impl<UT, E> Lift<UT> for EnumSet<E> where E: Finite);
+derive_ffi_traits!(impl<UT, E> LowerReturn<UT> for EnumSet<E> where E: Finite);
+derive_ffi_traits!(impl<UT, E> LiftReturn<UT> for EnumSet<E> where E: Finite);
+derive_ffi_traits!(impl<UT, E> LiftRef<UT> for EnumSet<E> where E: Finite);
+derive_ffi_traits!(impl<UT, E> TypeId<UT> for EnumSet<E> where E: Finite);
+
+#[cfg(test)]
+mod tests {
+    use uniffi_core::{Lift, Lower};
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use EnumSet;
+
+    struct Tag;
+
+    #[test]
+    fn test_lower_lift() {
+        assert_eq!(0b101, <EnumSet<Foo> as Lower<Tag>>::lower(set(&[A, C])));
+        assert_eq!(set(&[A, C]), <EnumSet<Foo> as Lift<Tag>>::try_lift(0b101).unwrap());
+        assert!(<EnumSet<Foo> as Lift<Tag>>::try_lift(0b1000).is_err());
+    }
+
+    #[test]
+    fn test_write_read() {
+        let mut buf = Vec::new();
+        <EnumSet<Foo> as Lower<Tag>>::write(set(&[B]), &mut buf);
+        assert_eq!(4, buf.len());
+        let mut slice = &buf[..];
+        assert_eq!(set(&[B]), <EnumSet<Foo> as Lift<Tag>>::try_read(&mut slice).unwrap());
+        assert!(slice.is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A binary encoding of sets which detects changes to the enum.
+//!
+//! An encoded set is nine bytes long: a format version, a little-endian
+//! 32-bit fingerprint of the enum, and the little-endian bit mask. The
+//! fingerprint is a hash of the name and discriminant of every variant, so
+//! adding, removing, renaming or reordering variants changes it.
+
+use std::error;
+use std::fmt;
+
+use {universe, EnumSet, Finite};
+
+const VERSION: u8 = 1;
+
+pub const ENCODED_LEN: usize = 9;
+
+/// An error returned when decoding a versioned set fails.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VersionedDecodeError {
+    /// The input is not nine bytes long.
+    InvalidLength,
+    /// The input was produced by an unsupported version of the encoding.
+    UnsupportedVersion(u8),
+    /// The input was produced for a different definition of the enum.
+    FingerprintMismatch {
+        /// The fingerprint of the enum being decoded.
+        expected: u32,
+        /// The fingerprint stored in the input.
+        found: u32,
+    },
+    /// The mask contains bits which do not correspond to a variant.
+    UnknownVariant,
+}
+
+impl fmt::Display for VersionedDecodeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VersionedDecodeError::InvalidLength => fmt.write_str("invalid length"),
+            VersionedDecodeError::UnsupportedVersion(version) => {
+                write!(fmt, "unsupported version {}", version)
+            }
+            VersionedDecodeError::FingerprintMismatch { expected, found } => {
+                write!(fmt, "enum fingerprint {:#010x} does not match {:#010x}", found, expected)
+            }
+            VersionedDecodeError::UnknownVariant => fmt.write_str("mask contains an unknown variant"),
+        }
+    }
+}
+
+impl error::Error for VersionedDecodeError {}
+
+/// Returns the 32-bit FNV-1a hash of the variants of `E`.
+pub fn fingerprint<E: Finite + fmt::Debug>() -> u32 {
+    let mut hash: u32 = 0x811c_9dc5;
+    let mut write = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash = (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193);
+        }
+    };
+    // The `Debug` representations are used rather than the names, so that
+    // changing `Finite::NAME_CASE` does not change the fingerprint.
+    for index in 0..E::VARIANT_COUNT {
+        // Safe because every value below `VARIANT_COUNT` is a variant.
+        let name = format!("{:?}", unsafe { E::from_u32(index) });
+        write(&index.to_le_bytes());
+        write(name.as_bytes());
+        write(&[0]);
+    }
+    hash
+}
+
+impl<E: Finite + fmt::Debug> EnumSet<E> {
+    /// The length of the encoding produced by `encode_versioned`.
+    pub const MAX_VERSIONED_LEN: usize = ENCODED_LEN;
+
+    /// Encodes the set along with a format version and a fingerprint of the
+    /// variants of `E`.
+    ///
+    /// The result can be decoded with `decode_versioned`, which fails if the
+    /// variants of `E` have changed in the meantime.
+    pub fn encode_versioned(&self) -> [u8; ENCODED_LEN] {
+        let mut bytes = [0; ENCODED_LEN];
+        bytes[0] = VERSION;
+        bytes[1..5].copy_from_slice(&fingerprint::<E>().to_le_bytes());
+        bytes[5..].copy_from_slice(&self.bits.to_le_bytes());
+        bytes
+    }
+
+    /// Decodes a set encoded with `encode_versioned`.
+    pub fn decode_versioned(bytes: &[u8]) -> Result<Self, VersionedDecodeError> {
+        if bytes.len() != ENCODED_LEN {
+            return Err(VersionedDecodeError::InvalidLength);
+        }
+        if bytes[0] != VERSION {
+            return Err(VersionedDecodeError::UnsupportedVersion(bytes[0]));
+        }
+        let expected = fingerprint::<E>();
+        let found = u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]);
+        if found != expected {
+            return Err(VersionedDecodeError::FingerprintMismatch { expected, found });
+        }
+        let bits = u32::from_le_bytes([bytes[5], bytes[6], bytes[7], bytes[8]]);
+        if bits & !universe::<E>() != 0 {
+            return Err(VersionedDecodeError::UnknownVariant);
+        }
+        Ok(EnumSet::new_with_bits(bits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+
+    use tests::{set, Foo};
+    use tests::Foo::*;
+
+    use {CLike, EnumSet, Finite};
+    use super::VersionedDecodeError;
+
+    #[allow(dead_code)]
+    #[derive(Copy, Clone, Debug)]
+    #[repr(u32)]
+    enum Renamed {
+        A, Bee, C
+    }
+
+    impl CLike for Renamed {
+        fn to_u32(&self) -> u32 {
+            *self as u32
+        }
+
+        unsafe fn from_u32(v: u32) -> Renamed {
+            mem::transmute(v)
+        }
+    }
+
+    unsafe impl Finite for Renamed {
+        const VARIANT_COUNT: u32 = 3;
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for s in EnumSet::<Foo>::exhaustive() {
+            assert_eq!(Ok(s), EnumSet::decode_versioned(&s.encode_versioned()));
+        }
+    }
+
+    #[test]
+    fn test_max_len() {
+        assert_eq!(EnumSet::<Foo>::MAX_VERSIONED_LEN, EnumSet::<Foo>::all().encode_versioned().len());
+    }
+
+    #[test]
+    fn test_errors() {
+        let bytes = set(&[A, C]).encode_versioned();
+        assert_eq!(Err(VersionedDecodeError::InvalidLength),
+                   EnumSet::<Foo>::decode_versioned(&bytes[..8]));
+
+        let mut wrong_version = bytes;
+        wrong_version[0] = 2;
+        assert_eq!(Err(VersionedDecodeError::UnsupportedVersion(2)),
+                   EnumSet::<Foo>::decode_versioned(&wrong_version));
+
+        match EnumSet::<Renamed>::decode_versioned(&bytes) {
+            Err(VersionedDecodeError::FingerprintMismatch { .. }) => {}
+            result => panic!("unexpected {:?}", result.map(|s| s.bits)),
+        }
+
+        let mut unknown = bytes;
+        unknown[5] = 0b1000;
+        assert_eq!(Err(VersionedDecodeError::UnknownVariant),
+                   EnumSet::<Foo>::decode_versioned(&unknown));
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::borrow;
+use std::fmt;
+use std::ops;
+
+use {CLike, EnumSet};
+
+/// A set which notifies a callback whenever its membership changes.
+///
+/// The callback receives the elements which were added and the elements
+/// which were removed by a mutation. It is not called for mutations which
+/// leave the set unchanged.
+///
+/// ```
+/// use enum_set::{CLike, EnumSet, WatchedEnumSet};
+/// use std::mem;
+/// use std::sync::mpsc;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u32)]
+/// enum Flag {
+///     Visible, Selected
+/// }
+///
+/// impl CLike for Flag {
+///     fn to_u32(&self) -> u32 {
+///         *self as u32
+///     }
+///
+///     unsafe fn from_u32(v: u32) -> Flag {
+///         mem::transmute(v)
+///     }
+/// }
+///
+/// let (sender, receiver) = mpsc::channel();
+/// let mut flags = WatchedEnumSet::new(EnumSet::new(), move |added, removed| {
+///     sender.send((added, removed)).unwrap();
+/// });
+/// flags.insert(Flag::Visible);
+/// flags.insert(Flag::Visible);
+///
+/// let (added, removed) = receiver.try_recv().unwrap();
+/// assert_eq!(vec![Flag::Visible], added.iter().collect::<Vec<_>>());
+/// assert!(removed.is_empty());
+/// assert!(receiver.try_recv().is_err());
+/// ```
+pub struct WatchedEnumSet<E, F> {
+    set: EnumSet<E>,
+    callback: F,
+}
+
+impl<E: CLike, F: FnMut(EnumSet<E>, EnumSet<E>)> WatchedEnumSet<E, F> {
+    /// Returns a `WatchedEnumSet` holding `set` and notifying `callback`.
+    pub fn new(set: EnumSet<E>, callback: F) -> Self {
+        WatchedEnumSet { set, callback }
+    }
+
+    /// Returns the contents of the set.
+    pub fn get(&self) -> EnumSet<E> {
+        EnumSet::new_with_bits(self.set.bits)
+    }
+
+    /// Returns the contents of the set, dropping the callback.
+    pub fn into_inner(self) -> EnumSet<E> {
+        self.set
+    }
+
+    /// Calls `f` with a mutable reference to the set, then notifies the
+    /// callback if the membership changed.
+    pub fn modify<R, G: FnOnce(&mut EnumSet<E>) -> R>(&mut self, f: G) -> R {
+        let old = self.set.bits;
+        let result = f(&mut self.set);
+        let new = self.set.bits;
+        if old != new {
+            (self.callback)(EnumSet::new_with_bits(new & !old),
+                            EnumSet::new_with_bits(old & !new));
+        }
+        result
+    }
+
+    /// Adds the given value to the set.
+    ///
+    /// Returns `true` if the value was not already present in the set.
+    pub fn insert(&mut self, value: E) -> bool {
+        self.modify(|set| set.insert(value))
+    }
+
+    /// Removes a value from the set.
+    ///
+    /// Returns `true` if the value was present in the set.
+    pub fn remove<V: borrow::Borrow<E>>(&mut self, value: V) -> bool {
+        self.modify(|set| set.remove(value))
+    }
+
+    /// Removes all elements from the set.
+    pub fn clear(&mut self) {
+        self.modify(|set| set.clear())
+    }
+
+    /// Replaces the contents of the set with `other`, returning the previous
+    /// contents.
+    pub fn replace_with(&mut self, other: EnumSet<E>) -> EnumSet<E> {
+        self.modify(|set| set.replace_with(other))
+    }
+}
+
+impl<E, F> ops::Deref for WatchedEnumSet<E, F> {
+    type Target = EnumSet<E>;
+
+    fn deref(&self) -> &EnumSet<E> {
+        &self.set
+    }
+}
+
+impl<E: CLike + fmt::Debug, F> fmt::Debug for WatchedEnumSet<E, F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_tuple("WatchedEnumSet").field(&self.set).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use tests::set;
+    use tests::Foo::*;
+
+    use super::WatchedEnumSet;
+
+    #[test]
+    fn test_notifications() {
+        let changes = RefCell::new(Vec::new());
+        let mut watched = WatchedEnumSet::new(set(&[A]), |added, removed| {
+            changes.borrow_mut().push((added, removed));
+        });
+
+        assert!(watched.insert(B));
+        assert!(!watched.insert(B));
+        assert!(watched.remove(A));
+        assert!(!watched.remove(A));
+        assert_eq!(set(&[B]), watched.replace_with(set(&[A, C])));
+        watched.modify(|set| set.insert(A));
+        watched.clear();
+        watched.clear();
+        assert!(watched.is_empty());
+
+        assert_eq!(vec![(set(&[B]), set(&[])),
+                        (set(&[]), set(&[A])),
+                        (set(&[A, C]), set(&[B])),
+                        (set(&[]), set(&[A, C]))],
+                   *changes.borrow());
+    }
+}
@@ -0,0 +1,47 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Zeroization of sets with `zeroize`.
+//!
+//! Zeroizing a set clears it with a volatile write, so the write is not
+//! optimized away. A set which should be cleared on drop can be wrapped in
+//! `zeroize::Zeroizing`.
+
+use zeroize::Zeroize;
+
+use {CLike, EnumSet};
+
+impl<E: CLike> Zeroize for EnumSet<E> {
+    fn zeroize(&mut self) {
+        self.bits.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zeroize::{Zeroize, Zeroizing};
+
+    use tests::set;
+    use tests::Foo::*;
+
+    #[test]
+    fn test_zeroize() {
+        let mut e = set(&[A, C]);
+        e.zeroize();
+        assert!(e.is_empty());
+    }
+
+    #[test]
+    fn test_zeroizing() {
+        let mut e = Zeroizing::new(set(&[B]));
+        e.insert(C);
+        assert_eq!(set(&[B, C]), *e);
+    }
+}